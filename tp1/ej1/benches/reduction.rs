@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ej1::barrett::BarrettContext;
+use ej1::big_int::{BigInt, BigUint};
+use ej1::finite_field_element::FiniteFieldElement;
+use ej1::montgomery::{MontgomeryContext, MontgomeryFieldElement};
+
+// moduli of the curves in main.rs plus the NIST P-256 and P-521 primes
+fn moduli() -> Vec<(&'static str, BigUint)> {
+    vec![
+        ("p43", BigUint::from(43u32)),
+        ("p1021", BigUint::from(1021u32)),
+        ("p256", "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap()),
+        ("p521", (BigUint::one() << 521) - BigUint::one()),
+    ]
+}
+
+// compares the plain division, montgomery and barrett reduction strategies for mul and pow
+fn reduction(c: &mut Criterion) {
+    let mut mul_group = c.benchmark_group("mul");
+    for (name, p) in moduli() {
+        let a = FiniteFieldElement::new(&p - &BigUint::from(3u32), p.clone());
+        let b = FiniteFieldElement::new(&p >> 1, p.clone());
+        let barrett = BarrettContext::new(p.clone()).unwrap();
+        let montgomery = Arc::new(MontgomeryContext::new(p.clone()).unwrap());
+        let ma = MontgomeryFieldElement::from_element(&a, &montgomery).unwrap();
+        let mb = MontgomeryFieldElement::from_element(&b, &montgomery).unwrap();
+
+        mul_group.bench_with_input(BenchmarkId::new("division", name), &(), |bench, _| {
+            bench.iter(|| black_box(&a).mul(black_box(&b)).unwrap())
+        });
+        mul_group.bench_with_input(BenchmarkId::new("barrett", name), &(), |bench, _| {
+            bench.iter(|| barrett.mul(black_box(&a), black_box(&b)).unwrap())
+        });
+        mul_group.bench_with_input(BenchmarkId::new("montgomery", name), &(), |bench, _| {
+            bench.iter(|| black_box(&ma).mul(black_box(&mb)).unwrap())
+        });
+    }
+    mul_group.finish();
+
+    let mut pow_group = c.benchmark_group("pow");
+    for (name, p) in moduli() {
+        // full size exponent p - 2 (fermat inversion)
+        let exponent = BigInt::from(&p - &BigUint::from(2u32));
+        let a = FiniteFieldElement::new(&p >> 1, p.clone());
+        let barrett = BarrettContext::new(p.clone()).unwrap();
+        let montgomery = Arc::new(MontgomeryContext::new(p.clone()).unwrap());
+        let ma = MontgomeryFieldElement::from_element(&a, &montgomery).unwrap();
+
+        pow_group.bench_with_input(BenchmarkId::new("division", name), &(), |bench, _| {
+            bench.iter(|| black_box(&a).pow(exponent.clone()).unwrap())
+        });
+        pow_group.bench_with_input(BenchmarkId::new("barrett", name), &(), |bench, _| {
+            bench.iter(|| barrett.pow(black_box(&a), exponent.clone()).unwrap())
+        });
+        pow_group.bench_with_input(BenchmarkId::new("montgomery", name), &(), |bench, _| {
+            bench.iter(|| black_box(&ma).pow(exponent.clone()).unwrap())
+        });
+    }
+    pow_group.finish();
+}
+
+criterion_group!(benches, reduction);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ej1::big_int::BigUint;
+use ej1::elliptic_curve::EllipticCurve;
+use ej1::finite_field_element::FiniteFieldElement;
+use ej1::fixed_base::FixedBaseTable;
+use ej1::point::Point;
+
+fn point(curve: &EllipticCurve, x: BigUint, y: BigUint) -> Point {
+    let p = curve.field.modulus.clone();
+    Point::new(Some(FiniteFieldElement::new(x, p.clone())), Some(FiniteFieldElement::new(y, p)), curve.clone()).unwrap()
+}
+
+// curves of main.rs with their points and scalars, plus the NIST P-256 generator with a full size scalar
+fn cases() -> Vec<(&'static str, Point, BigUint)> {
+    let curve = EllipticCurve::new(0, 6, 43).unwrap();
+    let p43 = point(&curve, BigUint::from(13u32), BigUint::from(15u32));
+    let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+    let p1021 = point(&curve, BigUint::from(379u32), BigUint::from(1011u32));
+
+    let p: BigUint = "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap();
+    let b: BigUint = "0x5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b".parse().unwrap();
+    let gx: BigUint = "0x6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296".parse().unwrap();
+    let gy: BigUint = "0x4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5".parse().unwrap();
+    let k: BigUint = "0xc51e4753afdec1e6b6c6a5b992f43f8dd0c7a8933072708b6522468b2ffb06fd".parse().unwrap();
+    let curve = EllipticCurve::new(-3, b, p).unwrap();
+    let p256 = point(&curve, gx, gy);
+
+    vec![
+        ("p43", p43, BigUint::from(11u32)),
+        ("p1021", p1021, BigUint::from(655u32)),
+        ("p256", p256, k),
+    ]
+}
+
+// prime orders of the points of cases
+fn orders() -> Vec<BigUint> {
+    vec![
+        BigUint::from(13u32),
+        BigUint::from(1039u32),
+        "0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551".parse().unwrap(),
+    ]
+}
+
+// double and add against the width-w NAF for a few windows
+fn scalar_mul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scalar_mul");
+    for (name, point, k) in cases() {
+        group.bench_with_input(BenchmarkId::new("double_and_add", name), &(), |bench, _| {
+            bench.iter(|| black_box(&point).scalar_mul_left_to_right(black_box(&k)).unwrap())
+        });
+        for width in [2, 3, 4, 5, 6] {
+            group.bench_with_input(BenchmarkId::new(format!("wnaf_{}", width), name), &(), |bench, _| {
+                bench.iter(|| black_box(&point).scalar_mul_wnaf(black_box(&k), width).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+// key generation kG: montgomery ladder against the fixed base tables of the generator
+fn fixed_base(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fixed_base");
+    for ((name, point, k), order) in cases().into_iter().zip(orders()) {
+        group.bench_with_input(BenchmarkId::new("ladder", name), &(), |bench, _| {
+            bench.iter(|| black_box(&point).scalar_mul_ladder(black_box(&k), &order).unwrap())
+        });
+        for window in [2, 4, 6] {
+            let table = FixedBaseTable::new(&point, &order, window).unwrap();
+            group.bench_with_input(BenchmarkId::new(format!("table_{}", window), name), &(), |bench, _| {
+                bench.iter(|| table.mul(black_box(&k)).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scalar_mul, fixed_base);
+criterion_main!(benches);
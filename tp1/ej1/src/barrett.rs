@@ -0,0 +1,125 @@
+use crate::big_int::{BigInt, BigUint};
+use crate::finite_field_element::FiniteFieldElement;
+
+// precomputed constant to reduce modulo a fixed modulus m with Barrett's method
+// with k = bits(m) and mu = floor(4^k / m), the quotient of x / m for x < m^2 is approximated
+// with shifts and one multiplication: q = ((x >> (k - 1)) * mu) >> (k + 1), which is at most 2 below the real one
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarrettContext {
+    pub modulus: BigUint,
+    k: usize,
+    mu: BigUint,
+}
+
+impl BarrettContext {
+    pub fn new(modulus: BigUint) -> Result<BarrettContext, String> {
+        if modulus.is_zero() {
+            return Err("Zero division".to_string());
+        }
+        let k = modulus.bits();
+        let mu = &(BigUint::one() << (2 * k)) / &modulus;
+        Ok(BarrettContext {
+            modulus,
+            k,
+            mu,
+        })
+    }
+
+    // x mod m for x < m^2
+    // algorithm:
+    // 1. q <- ((x >> (k - 1)) * mu) >> (k + 1)
+    // 2. r <- x - q * m
+    // 3. while r >= m do r <- r - m   (at most twice)
+    pub fn reduce(&self, x: &BigUint) -> BigUint {
+        if *x < self.modulus {
+            return x.clone();
+        }
+        let q = &(&(x >> (self.k - 1)) * &self.mu) >> (self.k + 1);
+        let mut r = x - &(&q * &self.modulus);
+        while r >= self.modulus {
+            r = &r - &self.modulus;
+        }
+        r
+    }
+
+    fn check_modulus(&self, b: &FiniteFieldElement) -> Result<(), String> {
+        if self.modulus != b.modulus {
+            Err("Modulus mismatch".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn mul(&self, a: &FiniteFieldElement, b: &FiniteFieldElement) -> Result<FiniteFieldElement, String> {
+        self.check_modulus(a)?;
+        self.check_modulus(b)?;
+        Ok(FiniteFieldElement::from_reduced(self.reduce(&(&a.value * &b.value)), self.modulus.clone()))
+    }
+
+    // square and multiply like FiniteFieldElement::pow with every product reduced with Barrett
+    pub fn pow<E: Into<BigInt>>(&self, a: &FiniteFieldElement, b: E) -> Result<FiniteFieldElement, String> {
+        self.check_modulus(a)?;
+        let exponent = b.into();
+        let base = if exponent.is_negative() {
+            FiniteFieldElement::new(1, self.modulus.clone()).div(a)?
+        } else {
+            a.clone()
+        };
+        let exponent = exponent.magnitude();
+        let mut result = FiniteFieldElement::new(1, self.modulus.clone());
+        for i in (0..exponent.bits()).rev() {
+            result = self.mul(&result, &result)?;
+            if exponent.bit(i) {
+                result = self.mul(&result, &base)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+// tests for the barrett reduction
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p256() -> BigUint {
+        "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap()
+    }
+
+    #[test]
+    fn test_reduce() {
+        for p in [BigUint::from(43u32), BigUint::from(1021u32), p256()] {
+            let context = BarrettContext::new(p.clone()).unwrap();
+            let max = &p - &BigUint::one();
+            for x in [BigUint::zero(), max.clone(), p.clone(), &max * &max, &(&max * &max) - &max] {
+                assert_eq!(context.reduce(&x), &x % &p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_pow() {
+        // the moduli of the curves used in main.rs
+        for p in [43, 1021] {
+            let context = BarrettContext::new(BigUint::from(p as u32)).unwrap();
+            for v in 0..p {
+                let a = FiniteFieldElement::new(v, p);
+                let b = FiniteFieldElement::new(v * 7 + 3, p);
+                assert_eq!(context.mul(&a, &b).unwrap(), a.mul(&b).unwrap());
+                assert_eq!(context.pow(&a, 1000).unwrap(), a.pow(1000).unwrap());
+            }
+        }
+        let p = p256();
+        let context = BarrettContext::new(p.clone()).unwrap();
+        let a = FiniteFieldElement::new(&p >> 3, p.clone());
+        assert_eq!(context.pow(&a, BigInt::from(&p - &BigUint::from(2u32))).unwrap(), a.pow(-1).unwrap());
+    }
+
+    #[test]
+    fn test_modulus_mismatch() {
+        let context = BarrettContext::new(BigUint::from(43u32)).unwrap();
+        let a = FiniteFieldElement::new(3, 1021);
+        assert_eq!(context.mul(&a, &a).unwrap_err(), "Modulus mismatch");
+        assert!(BarrettContext::new(BigUint::zero()).is_err());
+    }
+}
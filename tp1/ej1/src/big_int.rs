@@ -0,0 +1,929 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, LowerHex, self};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Mul, Neg, Rem, Shl, Shr, Sub};
+use std::str::FromStr;
+
+// arbitrary precision unsigned integer
+// the value is stored as little endian 64 bit limbs. Arithmetic results are normalized (the most
+// significant limb is never zero and zero is an empty vector), but a value can be padded with zero
+// limbs up to a fixed width (see with_width), so equality, ordering and hashing only look at the
+// significant limbs
+#[derive(Debug, Clone, Default)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> BigUint {
+        BigUint { limbs: vec![1] }
+    }
+
+    pub fn from_limbs(limbs: Vec<u64>) -> BigUint {
+        let mut n = BigUint { limbs };
+        n.normalize();
+        n
+    }
+
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    // the same value padded with zero limbs to at least `limbs` limbs, values that are already
+    // wider are left as they are
+    pub fn with_width(mut self, limbs: usize) -> BigUint {
+        if self.limbs.len() < limbs {
+            self.limbs.resize(limbs, 0);
+        }
+        self
+    }
+
+    // the limbs without the zero limbs on top
+    fn significant(&self) -> &[u64] {
+        let len = self.limbs.iter().rposition(|&l| l != 0).map_or(0, |i| i + 1);
+        &self.limbs[..len]
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.significant().is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.significant() == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|l| l & 1 == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    // number of significant bits, zero has 0 bits
+    pub fn bits(&self) -> usize {
+        let limbs = self.significant();
+        match limbs.last() {
+            Some(top) => limbs.len() * 64 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    // value of the i-th bit (bit 0 is the least significant)
+    pub fn bit(&self, i: usize) -> bool {
+        let limb = i / 64;
+        limb < self.limbs.len() && (self.limbs[limb] >> (i % 64)) & 1 == 1
+    }
+
+    // swaps a and b when choice is true without branching on it: both are padded to the same number
+    // of limbs and every limb goes through the same masked xor. The results are not normalized, so
+    // both keep that width whatever the choice was
+    pub fn conditional_swap(a: &mut BigUint, b: &mut BigUint, choice: bool) {
+        let len = a.limbs.len().max(b.limbs.len());
+        a.limbs.resize(len, 0);
+        b.limbs.resize(len, 0);
+        // all ones when swapping, all zeros otherwise
+        let mask = 0u64.wrapping_sub(choice as u64);
+        for (x, y) in a.limbs.iter_mut().zip(b.limbs.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+
+    // width-w non adjacent form: digits d_i, least significant first, with self = sum of d_i 2^i where every
+    // digit is 0 or odd with |d_i| < 2^(w-1), and of any w consecutive digits at most one is not zero.
+    // while k > 0: if k is odd d = k mods 2^w (the residue in (-2^(w-1), 2^(w-1))) and k = k - d, which
+    // makes the next w - 1 bits zero, otherwise d = 0; then k = k / 2
+    pub fn wnaf(&self, width: usize) -> Result<Vec<i64>, String> {
+        if !(2..=32).contains(&width) {
+            return Err(format!("Window width must be between 2 and 32, got {}", width));
+        }
+        let modulus = 1i64 << width;
+        let mut k = self.clone();
+        let mut digits = Vec::with_capacity(self.bits() + 1);
+        while !k.is_zero() {
+            let mut digit = 0;
+            if k.is_odd() {
+                digit = (k.limbs[0] & (modulus as u64 - 1)) as i64;
+                if digit >= modulus / 2 {
+                    digit -= modulus;
+                }
+                k = if digit >= 0 {
+                    &k - &BigUint::from(digit as u64)
+                } else {
+                    &k + &BigUint::from(digit.unsigned_abs())
+                };
+            }
+            digits.push(digit);
+            k = &k >> 1;
+        }
+        Ok(digits)
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match *self.significant() {
+            [] => Some(0),
+            [l] => Some(l),
+            _ => None,
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        match *self.significant() {
+            [] => Some(0),
+            [l] => Some(l as u128),
+            [l0, l1] => Some(l0 as u128 | (l1 as u128) << 64),
+            _ => None,
+        }
+    }
+
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigUint, String> {
+        if !(2..=36).contains(&radix) {
+            return Err(format!("Invalid radix {}", radix));
+        }
+        let digits = s.trim().replace('_', "");
+        if digits.is_empty() {
+            return Err("Empty number".to_string());
+        }
+        let mut result = BigUint::zero();
+        for c in digits.chars() {
+            let digit = c.to_digit(radix).ok_or(format!("Invalid digit {} for radix {}", c, radix))?;
+            result = result.mul_small(radix as u64).add_small(digit as u64);
+        }
+        Ok(result)
+    }
+
+    fn mul_small(&self, m: u64) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry: u128 = 0;
+        for &l in &self.limbs {
+            let p = l as u128 * m as u128 + carry;
+            limbs.push(p as u64);
+            carry = p >> 64;
+        }
+        limbs.push(carry as u64);
+        BigUint::from_limbs(limbs)
+    }
+
+    fn add_small(&self, a: u64) -> BigUint {
+        add_limbs(self.significant(), &[a])
+    }
+
+    // short division, returns quotient and remainder
+    fn div_rem_small(&self, d: u64) -> (BigUint, u64) {
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut rem: u128 = 0;
+        for i in (0..self.limbs.len()).rev() {
+            let cur = rem << 64 | self.limbs[i] as u128;
+            quotient[i] = (cur / d as u128) as u64;
+            rem = cur % d as u128;
+        }
+        (BigUint::from_limbs(quotient), rem as u64)
+    }
+
+    // quotient and remainder of the euclidean division
+    // uses Knuth's algorithm D (The Art of Computer Programming vol 2, 4.3.1) for multi limb divisors
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        if divisor.is_zero() {
+            panic!("attempt to divide by zero");
+        }
+        if *self < *divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        let (dividend, divisor) = (self.significant(), divisor.significant());
+        if divisor.len() == 1 {
+            let (q, r) = self.div_rem_small(divisor[0]);
+            return (q, BigUint::from(r));
+        }
+        let n = divisor.len();
+        let m = dividend.len() - n;
+        // normalize so the top limb of the divisor has its highest bit set
+        let shift = divisor[n - 1].leading_zeros() as usize;
+        let vn = (BigUint::from_limbs(divisor.to_vec()) << shift).limbs;
+        let mut un = (self << shift).limbs;
+        un.resize(dividend.len() + 1, 0);
+
+        let mut quotient = vec![0u64; m + 1];
+        let base: u128 = 1 << 64;
+        for j in (0..=m).rev() {
+            let num = (un[j + n] as u128) << 64 | un[j + n - 1] as u128;
+            let mut qhat = num / vn[n - 1] as u128;
+            let mut rhat = num % vn[n - 1] as u128;
+            while qhat >= base || qhat * vn[n - 2] as u128 > (rhat << 64 | un[j + n - 2] as u128) {
+                qhat -= 1;
+                rhat += vn[n - 1] as u128;
+                if rhat >= base {
+                    break;
+                }
+            }
+            // multiply and subtract qhat * vn from the current window of un
+            let mut borrow: u64 = 0;
+            let mut carry: u128 = 0;
+            for i in 0..n {
+                let p = qhat * vn[i] as u128 + carry;
+                carry = p >> 64;
+                let (t, b1) = un[i + j].overflowing_sub(p as u64);
+                let (t, b2) = t.overflowing_sub(borrow);
+                un[i + j] = t;
+                borrow = b1 as u64 + b2 as u64;
+            }
+            let (t, b1) = un[j + n].overflowing_sub(carry as u64);
+            let (t, b2) = t.overflowing_sub(borrow);
+            un[j + n] = t;
+            // qhat was one too large, add the divisor back
+            if b1 || b2 {
+                qhat -= 1;
+                let mut carry: u128 = 0;
+                for i in 0..n {
+                    let s = un[i + j] as u128 + vn[i] as u128 + carry;
+                    un[i + j] = s as u64;
+                    carry = s >> 64;
+                }
+                un[j + n] = un[j + n].wrapping_add(carry as u64);
+            }
+            quotient[j] = qhat as u64;
+        }
+        un.truncate(n);
+        let remainder = BigUint::from_limbs(un) >> shift;
+        (BigUint::from_limbs(quotient), remainder)
+    }
+
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    // floor of the square root with newton's method, starting above the root so the iterates decrease
+    pub fn sqrt(&self) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let mut x = BigUint::one() << self.bits().div_ceil(2);
+        loop {
+            let next = &(&x + &(self / &x)) >> 1;
+            if next >= x {
+                return x;
+            }
+            x = next;
+        }
+    }
+
+    fn to_radix_string(&self, radix: u64, width: usize, chunk: u64) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        let mut chunks = Vec::new();
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = n.div_rem_small(chunk);
+            chunks.push(r);
+            n = q;
+        }
+        let mut s = String::new();
+        for (i, c) in chunks.iter().rev().enumerate() {
+            let digits = if radix == 10 { format!("{}", c) } else { format!("{:x}", c) };
+            if i == 0 {
+                s.push_str(&digits);
+            } else {
+                s.push_str(&"0".repeat(width - digits.len()));
+                s.push_str(&digits);
+            }
+        }
+        s
+    }
+}
+
+fn add_limbs(a: &[u64], b: &[u64]) -> BigUint {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut limbs = Vec::with_capacity(long.len() + 1);
+    let mut carry = false;
+    for (i, &l) in long.iter().enumerate() {
+        let (s, c1) = l.overflowing_add(*short.get(i).unwrap_or(&0));
+        let (s, c2) = s.overflowing_add(carry as u64);
+        limbs.push(s);
+        carry = c1 || c2;
+    }
+    limbs.push(carry as u64);
+    BigUint::from_limbs(limbs)
+}
+
+fn sub_limbs(a: &[u64], b: &[u64]) -> BigUint {
+    if b.len() > a.len() {
+        panic!("attempt to subtract with overflow");
+    }
+    let mut limbs = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for (i, &l) in a.iter().enumerate() {
+        let (d, b1) = l.overflowing_sub(*b.get(i).unwrap_or(&0));
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        limbs.push(d);
+        borrow = b1 || b2;
+    }
+    if borrow {
+        panic!("attempt to subtract with overflow");
+    }
+    BigUint::from_limbs(limbs)
+}
+
+// schoolbook multiplication
+fn mul_limbs(a: &[u64], b: &[u64]) -> BigUint {
+    if a.is_empty() || b.is_empty() {
+        return BigUint::zero();
+    }
+    let mut limbs = vec![0u64; a.len() + b.len()];
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry: u128 = 0;
+        for (j, &bj) in b.iter().enumerate() {
+            let t = ai as u128 * bj as u128 + limbs[i + j] as u128 + carry;
+            limbs[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        limbs[i + b.len()] = carry as u64;
+    }
+    BigUint::from_limbs(limbs)
+}
+
+impl PartialEq for BigUint {
+    fn eq(&self, other: &BigUint) -> bool {
+        self.significant() == other.significant()
+    }
+}
+
+impl Eq for BigUint {}
+
+impl Hash for BigUint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant().hash(state);
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        let (a, b) = (self.significant(), other.significant());
+        a.len().cmp(&b.len()).then_with(|| {
+            for i in (0..a.len()).rev() {
+                match a[i].cmp(&b[i]) {
+                    Ordering::Equal => continue,
+                    ord => return ord,
+                }
+            }
+            Ordering::Equal
+        })
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// implements a binary operator for every owned / borrowed combination given the borrowed one
+macro_rules! forward_binop {
+    ($t:ty, $tr:ident, $method:ident) => {
+        impl $tr<$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $tr<&$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: &$t) -> $t {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $tr<$t> for &$t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                self.$method(&rhs)
+            }
+        }
+    };
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: &BigUint) -> BigUint {
+        add_limbs(self.significant(), rhs.significant())
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        sub_limbs(self.significant(), rhs.significant())
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        mul_limbs(self.significant(), rhs.significant())
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+forward_binop!(BigUint, Add, add);
+forward_binop!(BigUint, Sub, sub);
+forward_binop!(BigUint, Mul, mul);
+forward_binop!(BigUint, Div, div);
+forward_binop!(BigUint, Rem, rem);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+    fn shl(self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let limb_shift = bits / 64;
+        let bit_shift = bits % 64;
+        let mut limbs = vec![0u64; limb_shift];
+        if bit_shift == 0 {
+            limbs.extend_from_slice(&self.limbs);
+        } else {
+            let mut carry = 0;
+            for &l in &self.limbs {
+                limbs.push(l << bit_shift | carry);
+                carry = l >> (64 - bit_shift);
+            }
+            limbs.push(carry);
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+    fn shr(self, bits: usize) -> BigUint {
+        let limb_shift = bits / 64;
+        if limb_shift >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let bit_shift = bits % 64;
+        let src = &self.limbs[limb_shift..];
+        let mut limbs = Vec::with_capacity(src.len());
+        for i in 0..src.len() {
+            if bit_shift == 0 {
+                limbs.push(src[i]);
+            } else {
+                let high = if i + 1 < src.len() { src[i + 1] << (64 - bit_shift) } else { 0 };
+                limbs.push(src[i] >> bit_shift | high);
+            }
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+    fn shl(self, bits: usize) -> BigUint {
+        &self << bits
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+    fn shr(self, bits: usize) -> BigUint {
+        &self >> bits
+    }
+}
+
+macro_rules! biguint_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(n: $t) -> BigUint {
+                    let n = n as u128;
+                    BigUint::from_limbs(vec![n as u64, (n >> 64) as u64])
+                }
+            }
+        )*
+    };
+}
+
+biguint_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<BigUint, String> {
+        match s.trim().strip_prefix("0x") {
+            Some(hex) => BigUint::from_str_radix(hex, 16),
+            None => BigUint::from_str_radix(s, 10),
+        }
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_radix_string(10, 19, 10_000_000_000_000_000_000))
+    }
+}
+
+impl LowerHex for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_radix_string(16, 15, 1 << 60))
+    }
+}
+
+// arbitrary precision signed integer stored as sign and magnitude, zero is never negative
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn one() -> BigInt {
+        BigInt::from(BigUint::one())
+    }
+
+    pub fn from_parts(negative: bool, magnitude: BigUint) -> BigInt {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_one(&self) -> bool {
+        !self.negative && self.magnitude.is_one()
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn into_magnitude(self) -> BigUint {
+        self.magnitude
+    }
+
+    // truncated division (quotient rounded towards zero), like the primitive `/` and `%`
+    pub fn div_rem(&self, divisor: &BigInt) -> (BigInt, BigInt) {
+        let (q, r) = self.magnitude.div_rem(&divisor.magnitude);
+        (
+            BigInt::from_parts(self.negative != divisor.negative, q),
+            BigInt::from_parts(self.negative, r),
+        )
+    }
+
+    // least non negative residue modulo `modulus`
+    pub fn rem_euclid(&self, modulus: &BigUint) -> BigUint {
+        let r = &self.magnitude % modulus;
+        if self.negative && !r.is_zero() {
+            modulus - &r
+        } else {
+            r
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+        // different signs, subtract the smaller magnitude from the bigger one
+        match self.magnitude.cmp(&rhs.magnitude) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, &rhs.magnitude - &self.magnitude),
+            _ => BigInt::from_parts(self.negative, &self.magnitude - &rhs.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &(-rhs)
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != rhs.negative, &self.magnitude * &rhs.magnitude)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+forward_binop!(BigInt, Add, add);
+forward_binop!(BigInt, Sub, sub);
+forward_binop!(BigInt, Mul, mul);
+forward_binop!(BigInt, Div, div);
+forward_binop!(BigInt, Rem, rem);
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> BigInt {
+        BigInt::from_parts(false, magnitude)
+    }
+}
+
+impl From<&BigUint> for BigInt {
+    fn from(magnitude: &BigUint) -> BigInt {
+        BigInt::from_parts(false, magnitude.clone())
+    }
+}
+
+macro_rules! bigint_from_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                #[allow(unused_comparisons)]
+                fn from(n: $t) -> BigInt {
+                    BigInt::from_parts(n < 0, BigUint::from((n as i128).unsigned_abs()))
+                }
+            }
+        )*
+    };
+}
+
+bigint_from_primitive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for BigInt {
+    fn from(n: u128) -> BigInt {
+        BigInt::from(BigUint::from(n))
+    }
+}
+
+impl TryFrom<BigInt> for BigUint {
+    type Error = String;
+
+    fn try_from(n: BigInt) -> Result<BigUint, String> {
+        if n.negative {
+            Err(format!("{} is negative", n))
+        } else {
+            Ok(n.magnitude)
+        }
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<BigInt, String> {
+        let s = s.trim();
+        match s.strip_prefix('-') {
+            Some(rest) => Ok(BigInt::from_parts(true, rest.parse()?)),
+            None => Ok(BigInt::from_parts(false, s.parse()?)),
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+// tests for the big integer operations
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let n = big("115792089210356248762697446949827177041145205346823718018223227024416415997951");
+        assert_eq!(n.to_string(), "115792089210356248762697446949827177041145205346823718018223227024416415997951");
+        assert_eq!(big("0xff").to_string(), "255");
+        assert_eq!(format!("{:x}", big("1000000000000000000000")), "3635c9adc5dea00000");
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!("-42".parse::<BigInt>().unwrap(), BigInt::from(-42));
+        assert!(BigUint::from_str_radix("12z", 10).is_err());
+    }
+
+    #[test]
+    fn test_add_sub_carry() {
+        let max = BigUint::from(u64::MAX);
+        let sum = &max + &BigUint::one();
+        assert_eq!(sum.limbs(), &[0, 1]);
+        assert_eq!(&sum - &BigUint::one(), max);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sub_underflow() {
+        let _ = BigUint::one() - BigUint::from(2u32);
+    }
+
+    #[test]
+    fn test_mul() {
+        let a = BigUint::from(u128::MAX);
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1
+        let expected = (BigUint::one() << 256) - (BigUint::one() << 129) + BigUint::one();
+        assert_eq!(&a * &a, expected);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let a = big("340282366920938463463374607431768211457123456789");
+        let b = big("18446744073709551629");
+        let (q, r) = a.div_rem(&b);
+        assert!(r < b);
+        assert_eq!(&q * &b + &r, a);
+
+        // multi limb divisor where the quotient estimate needs correction
+        let a = (BigUint::one() << 521) - BigUint::one();
+        let b = (BigUint::one() << 256) - (BigUint::one() << 224) + (BigUint::one() << 192) + (BigUint::one() << 96) - BigUint::one();
+        let (q, r) = a.div_rem(&b);
+        assert!(r < b);
+        assert_eq!(&q * &b + &r, a);
+    }
+
+    #[test]
+    fn test_shifts() {
+        let a = big("123456789012345678901234567890");
+        assert_eq!(&(&a << 77) >> 77, a);
+        assert_eq!(&a >> 200, BigUint::zero());
+        assert_eq!(BigUint::one() << 64, BigUint::from(1u128 << 64));
+    }
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(BigUint::zero().bits(), 0);
+        assert_eq!(BigUint::from(255u32).bits(), 8);
+        assert_eq!((BigUint::one() << 520).bits(), 521);
+        assert!(BigUint::from(5u32).bit(2));
+        assert!(!BigUint::from(5u32).bit(1));
+    }
+
+    #[test]
+    fn test_conditional_swap() {
+        let (x, y) = (BigUint::one() << 130, BigUint::from(7u32));
+        let (mut a, mut b) = (x.clone(), y.clone());
+        BigUint::conditional_swap(&mut a, &mut b, false);
+        assert_eq!((&a, &b), (&x, &y));
+        BigUint::conditional_swap(&mut a, &mut b, true);
+        assert_eq!((&a, &b), (&y, &x));
+        // both keep the padded width, equality ignores the zero limbs
+        assert_eq!((a.limbs().len(), b.limbs().len()), (3, 3));
+        let mut zero = BigUint::zero();
+        BigUint::conditional_swap(&mut a, &mut zero, true);
+        assert!(a.is_zero());
+        assert_eq!(zero, y);
+    }
+
+    #[test]
+    fn test_padded_values() {
+        let x = BigUint::from(7u32).with_width(3);
+        assert_eq!(x.limbs().len(), 3);
+        assert_eq!(x, BigUint::from(7u32));
+        assert_eq!(x.bits(), 3);
+        assert_eq!(x.to_u64(), Some(7));
+        assert!(BigUint::zero().with_width(2).is_zero());
+        assert!(BigUint::one().with_width(2).is_one());
+        assert!(x < BigUint::one() << 64);
+        let big = (BigUint::one() << 130) + BigUint::from(5u32);
+        // 2^130 = 2 mod 7
+        assert_eq!(&big % &x, BigUint::zero());
+        assert_eq!(&x - &BigUint::from(2u32).with_width(4), BigUint::from(5u32));
+        let divisor = (BigUint::one() << 70).with_width(4);
+        assert_eq!(big.div_rem(&divisor), (BigUint::one() << 60, BigUint::from(5u32)));
+        let mut hashes = std::collections::HashSet::new();
+        hashes.insert(x);
+        assert!(hashes.contains(&BigUint::from(7u32)));
+    }
+
+    #[test]
+    fn test_wnaf() {
+        // 7 = 8 - 1 and 655 = 512 + 128 + 16 - 1 in non adjacent form
+        assert_eq!(BigUint::from(7u32).wnaf(2).unwrap(), vec![-1, 0, 0, 1]);
+        assert_eq!(BigUint::from(655u32).wnaf(2).unwrap(), vec![-1, 0, 0, 0, 1, 0, 0, 1, 0, 1]);
+        assert!(BigUint::zero().wnaf(4).unwrap().is_empty());
+        assert!(BigUint::one().wnaf(1).is_err());
+
+        let k: BigUint = "0x1f3a9b7c5d2e4f6a8b0c1d2e3f405162738495a6b7c8d9eaf0b1c2d3e4f50617".parse().unwrap();
+        for width in 2..=8 {
+            let digits = k.wnaf(width).unwrap();
+            let mut value = BigInt::from(0);
+            for (i, d) in digits.iter().enumerate() {
+                assert!(*d == 0 || (d % 2 != 0 && d.abs() < 1 << (width - 1)));
+                // a non zero digit is followed by at least w - 1 zeros
+                if *d != 0 {
+                    assert!(digits[i + 1..].iter().take(width - 1).all(|d| *d == 0));
+                }
+                value = &value + &(&BigInt::from(*d) * &BigInt::from(BigUint::one() << i));
+            }
+            assert_eq!(value, BigInt::from(k.clone()));
+            assert_ne!(*digits.last().unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        for n in 0..2000u64 {
+            let root = BigUint::from(n).sqrt().to_u64().unwrap();
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+        let p521 = (BigUint::one() << 521) - BigUint::one();
+        let root = p521.sqrt();
+        assert!(&root * &root <= p521);
+        assert!(&(&root + &BigUint::one()) * &(&root + &BigUint::one()) > p521);
+        assert_eq!((BigUint::one() << 520).sqrt(), BigUint::one() << 260);
+    }
+
+    #[test]
+    fn test_signed_arithmetic() {
+        let a = BigInt::from(-7);
+        let b = BigInt::from(3);
+        assert_eq!(&a + &b, BigInt::from(-4));
+        assert_eq!(&b - &a, BigInt::from(10));
+        assert_eq!(&a * &b, BigInt::from(-21));
+        assert_eq!(a.div_rem(&b), (BigInt::from(-2), BigInt::from(-1)));
+        assert_eq!(a.rem_euclid(&BigUint::from(3u32)), BigUint::from(2u32));
+        assert_eq!(-BigInt::zero(), BigInt::zero());
+        assert!(a < b);
+    }
+}
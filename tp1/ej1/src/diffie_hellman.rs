@@ -1,25 +1,115 @@
 use std::sync::{mpsc::{Sender, Receiver}, Arc, Mutex};
 
-use crate::{point::Point};
+use crate::{big_int::BigUint, domain_parameters::ValidatedDomainParameters, point::Point};
 
 pub struct DiffieHellman {}
 
 impl DiffieHellman {
 
-    pub fn calculate_shared_key(generator: &Point, random_number: u32, sender: Sender<Point>, receiver: Arc<Mutex<Receiver<Point>>> ) -> Point {
-        let receiver = receiver.lock().unwrap();
+    // only validated domain parameters are accepted, a generator of small or composite order would leak
+    // the shared key (see DomainParameters::validate). The public key received is validated too (see
+    // ValidatedDomainParameters::validate_public_key), the shared key is not computed for an invalid one.
+    // The secret must not be a multiple of the order n, see ValidatedDomainParameters::random_secret
+    pub fn calculate_shared_key(parameters: &ValidatedDomainParameters, random_number: &BigUint, sender: Sender<Point>, receiver: Arc<Mutex<Receiver<Point>>> ) -> Result<Point, String> {
+        // con un secreto multiplo de n la clave publica y la compartida son el punto en el infinito
+        if (random_number % parameters.order()).is_zero() {
+            return Err(format!("The secret {} is a multiple of the order {}", random_number, parameters.order()));
+        }
+        let receiver = receiver.lock().map_err(|_| "Error locking the receiver".to_string())?;
         // actor_name calcula su punto publico con el numero random ( B = b * g )
-        let actor_name_public_key = generator.scalar_mul(random_number).unwrap();
+        // con la tabla precalculada del generador (o el montgomery ladder) la secuencia de operaciones del cuerpo
+        // no depende de los bits del secreto, pero la aritmetica de BigUint de cada operacion no es de tiempo
+        // constante
+        let actor_name_public_key = parameters.generator_mul(random_number)?;
     
         // actor_name envia su clave publica a other_actor
-        sender.send(actor_name_public_key).unwrap();
+        sender.send(actor_name_public_key).map_err(|_| "Error sending the public key".to_string())?;
     
         // actor_name recibe la clave publica de other_actor
-        let other_actor_public_key = receiver.recv().unwrap();
+        let other_actor_public_key = receiver.recv().map_err(|_| "Error receiving the public key".to_string())?;
+
+        // se rechaza una clave publica de otra curva, el punto en el infinito o un punto fuera del subgrupo de
+        // orden n, multiplicarla por el secreto filtraria el secreto modulo el orden del punto
+        parameters.validate_public_key(&other_actor_public_key)?;
     
         // actor_name calcula la clave secreta compartida con other_actor public key (aca K = b * A)
-        let shared_key = other_actor_public_key.scalar_mul(random_number).unwrap();
+        // el montgomery ladder reduce el secreto modulo n y resuelve los casos excepcionales con swaps
+        // condicionales, sin las ramas de Point::add
+        other_actor_public_key.scalar_mul_ladder(random_number, parameters.order())
+    }
+}
+
+// tests for the key agreement with a public key already in the channel
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+    use crate::domain_parameters::DomainParameters;
+    use crate::elliptic_curve::EllipticCurve;
+    use crate::finite_field_element::FiniteFieldElement;
+
+    fn point(x: i64, y: i64, curve: &EllipticCurve) -> Point {
+        let p = curve.field.modulus.clone();
+        Point::new(Some(FiniteFieldElement::new(x, p.clone())), Some(FiniteFieldElement::new(y, p)), curve.clone()).unwrap()
+    }
+
+    fn parameters() -> ValidatedDomainParameters {
+        let curve = EllipticCurve::new(0, 6, 43).unwrap();
+        DomainParameters::new(curve.clone(), point(13, 15, &curve), BigUint::from(13u32), BigUint::from(3u32))
+            .validate()
+            .unwrap()
+    }
+
+    // shared key of `random_number` when the other party sends `public_key`, and the public key it sent
+    fn shared_key(parameters: &ValidatedDomainParameters, random_number: u32, public_key: Point) -> (Result<Point, String>, Option<Point>) {
+        let (sender, sent) = channel();
+        let (other_sender, receiver) = channel();
+        other_sender.send(public_key).unwrap();
+        let random_number = BigUint::from(random_number);
+        let key = DiffieHellman::calculate_shared_key(parameters, &random_number, sender, Arc::new(Mutex::new(receiver)));
+        (key, sent.try_recv().ok())
+    }
+
+    #[test]
+    fn test_shared_key() {
+        let parameters = parameters();
+        let (a, b) = (5, 11);
+        let (key, public_a) = shared_key(&parameters, a, parameters.generator_mul(b).unwrap());
+        assert_eq!(public_a.unwrap(), parameters.generator_mul(a).unwrap());
+        assert_eq!(key.unwrap(), parameters.generator_mul(a * b).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_public_key() {
+        let parameters = parameters();
+        let curve = parameters.curve().clone();
+        // (9, 2) generates the whole group of 39 points and 13 (9, 2) has order 3
+        let small_order = point(9, 2, &curve).scalar_mul(13).unwrap();
+        let (key, _) = shared_key(&parameters, 5, small_order);
+        assert!(key.unwrap_err().contains("does not have order 13"));
+        let (key, _) = shared_key(&parameters, 5, point(9, 2, &curve));
+        assert!(key.unwrap_err().contains("does not have order 13"));
+
+        let (key, _) = shared_key(&parameters, 5, Point::new(None, None, curve.clone()).unwrap());
+        assert_eq!(key.unwrap_err(), "Public key can't be the point at infinity");
+
+        // a point of another curve
+        let other = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let (key, _) = shared_key(&parameters, 5, point(379, 1011, &other));
+        assert!(key.unwrap_err().contains("does not belong to"));
+    }
 
-        shared_key
+    #[test]
+    fn test_invalid_secret() {
+        // multiples of n = 13 are rejected before sending a public key
+        let parameters = parameters();
+        let public_key = parameters.generator_mul(3).unwrap();
+        for secret in [0, 13, 26] {
+            let (key, sent) = shared_key(&parameters, secret, public_key.clone());
+            assert_eq!(key.unwrap_err(), format!("The secret {} is a multiple of the order 13", secret));
+            assert!(sent.is_none());
+        }
+        let (key, _) = shared_key(&parameters, 14, public_key.clone());
+        assert_eq!(key.unwrap(), parameters.generator_mul(3).unwrap());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,257 @@
+use std::fmt::{Display, Formatter, self};
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::big_int::{BigInt, BigUint};
+use crate::elliptic_curve::EllipticCurve;
+use crate::fixed_base::{FixedBaseTable, FIXED_BASE_WINDOW};
+use crate::point::Point;
+use crate::primality::is_prime;
+
+// elliptic curve domain parameters (curve, generator G, order n of G, cofactor h) as claimed by
+// whoever proposes them, nothing is checked until validate
+#[derive(Debug, PartialEq, Clone)]
+pub struct DomainParameters {
+    pub curve: EllipticCurve,
+    pub generator: Point,
+    pub order: BigUint,
+    pub cofactor: BigUint,
+}
+
+// domain parameters that passed DomainParameters::validate, the fields are private so the only way
+// to build one is validating.
+// they keep a fixed base table of the generator for key generation, shared between clones. It needs a
+// short weierstrass curve and an odd order, otherwise generator_mul uses the montgomery ladder
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidatedDomainParameters {
+    curve: EllipticCurve,
+    generator: Point,
+    order: BigUint,
+    cofactor: BigUint,
+    fixed_base: Option<Arc<FixedBaseTable>>,
+}
+
+impl DomainParameters {
+    pub fn new(curve: EllipticCurve, generator: Point, order: BigUint, cofactor: BigUint) -> DomainParameters {
+        DomainParameters {
+            curve,
+            generator,
+            order,
+            cofactor,
+        }
+    }
+
+    // checks that:
+    // - the field has characteristic bigger than 3, so the curve has a short weierstrass form for the
+    //   montgomery ladder of generator_mul and DiffieHellman
+    // - the generator is an affine point of the curve
+    // - the order n is prime and n G = O, so G generates a subgroup of n points
+    // - h n is the number of points of the curve
+    pub fn validate(self) -> Result<ValidatedDomainParameters, String> {
+        if self.curve.field.modulus <= BigUint::from(3u32) {
+            return Err(format!("Domain parameters need a characteristic bigger than 3, got {}", self.curve.field));
+        }
+        let generator = &self.generator;
+        if !generator.curve.eq(&self.curve) {
+            return Err(format!("Generator {} does not belong to {}", generator, self.curve));
+        }
+        let (x, y) = match (&generator.x, &generator.y) {
+            (Some(x), Some(y)) => (x.clone(), y.clone()),
+            _ => return Err("Generator can't be the point at infinity".to_string()),
+        };
+        if !self.curve.check_point(x, y) {
+            return Err(format!("Generator {} does not exist on: {}", generator, self.curve));
+        }
+        if !is_prime(&self.order) {
+            return Err(format!("Order {} of the generator is not prime", self.order));
+        }
+        if !generator.scalar_mul_montgomery(&self.order)?.is_infinity() {
+            return Err(format!("Generator {} does not have order {}", generator, self.order));
+        }
+        let points = self.curve.order()?;
+        if &self.cofactor * &self.order != points {
+            return Err(format!(
+                "Cofactor {} times order {} is not the number of points {} of the curve",
+                self.cofactor, self.order, points
+            ));
+        }
+        let fixed_base = if self.curve.is_short() && self.order.is_odd() {
+            Some(Arc::new(FixedBaseTable::new(&self.generator, &self.order, FIXED_BASE_WINDOW)?))
+        } else {
+            None
+        };
+        Ok(ValidatedDomainParameters {
+            curve: self.curve,
+            generator: self.generator,
+            order: self.order,
+            cofactor: self.cofactor,
+            fixed_base,
+        })
+    }
+}
+
+impl ValidatedDomainParameters {
+    pub fn curve(&self) -> &EllipticCurve {
+        &self.curve
+    }
+
+    pub fn generator(&self) -> &Point {
+        &self.generator
+    }
+
+    pub fn order(&self) -> &BigUint {
+        &self.order
+    }
+
+    pub fn cofactor(&self) -> &BigUint {
+        &self.cofactor
+    }
+
+    // kG, from the precomputed table when there is one. Neither the table nor the ladder branches on the
+    // special cases of the group law (see Point::add), so k can be a secret
+    pub fn generator_mul<S: Into<BigInt>>(&self, scalar: S) -> Result<Point, String> {
+        match &self.fixed_base {
+            Some(table) => table.mul(scalar),
+            None => self.generator.scalar_mul_ladder(scalar, &self.order),
+        }
+    }
+
+    // random secret in [1, n - 1], one limb more than the order makes the bias of the reduction negligible.
+    // A multiple of n would give O as the public and the shared key
+    pub fn random_secret<R: Rng>(&self, rng: &mut R) -> BigUint {
+        let limbs = (0..=self.order.limbs().len()).map(|_| rng.gen::<u64>()).collect();
+        &(&BigUint::from_limbs(limbs) % &(&self.order - &BigUint::one())) + &BigUint::one()
+    }
+
+    // checks a public key received from the other party before using it: an affine point of the curve
+    // in the subgroup of order n (n Q = O), otherwise a point of small order or of another curve would
+    // leak the secret it is multiplied by
+    pub fn validate_public_key(&self, point: &Point) -> Result<(), String> {
+        if !point.curve.eq(&self.curve) {
+            return Err(format!("Public key {} does not belong to {}", point, self.curve));
+        }
+        let (x, y) = match (&point.x, &point.y) {
+            (Some(x), Some(y)) => (x.clone(), y.clone()),
+            _ => return Err("Public key can't be the point at infinity".to_string()),
+        };
+        if !self.curve.check_point(x, y) {
+            return Err(format!("Public key {} does not exist on: {}", point, self.curve));
+        }
+        if !point.scalar_mul_montgomery(&self.order)?.is_infinity() {
+            return Err(format!("Public key {} does not have order {}", point, self.order));
+        }
+        Ok(())
+    }
+}
+
+impl Display for ValidatedDomainParameters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, G = {}, n = {}, h = {}", self.curve, self.generator, self.order, self.cofactor)
+    }
+}
+
+// tests for the domain parameters validation
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_field_element::FiniteFieldElement;
+
+    fn point(x: i64, y: i64, curve: &EllipticCurve) -> Point {
+        let p = curve.field.modulus.clone();
+        Point::new(Some(FiniteFieldElement::new(x, p.clone())), Some(FiniteFieldElement::new(y, p)), curve.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        // curve of the diffie hellman exercise, 39 = 3 * 13 points
+        let curve = EllipticCurve::new(0, 6, 43).unwrap();
+        let parameters = DomainParameters::new(curve.clone(), point(13, 15, &curve), BigUint::from(13u32), BigUint::from(3u32))
+            .validate()
+            .unwrap();
+        assert_eq!(parameters.order(), &BigUint::from(13u32));
+        assert_eq!(parameters.cofactor(), &BigUint::from(3u32));
+        assert!(parameters.generator().eq(&point(13, 15, &curve)));
+        for k in 0..40 {
+            assert_eq!(parameters.generator_mul(k).unwrap(), point(13, 15, &curve).scalar_mul(k).unwrap());
+        }
+
+        // prime order curve of main.rs
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let parameters = DomainParameters::new(curve.clone(), point(379, 1011, &curve), BigUint::from(1039u32), BigUint::one());
+        let parameters = parameters.validate().unwrap();
+        assert_eq!(parameters.generator_mul(655).unwrap(), point(388, 60, &curve));
+        // the clones share the table
+        let clone = parameters.clone();
+        assert!(Arc::ptr_eq(clone.fixed_base.as_ref().unwrap(), parameters.fixed_base.as_ref().unwrap()));
+
+        // a generator of order 2 has no table
+        let curve = EllipticCurve::new(-1, 0, 43).unwrap();
+        let g = point(0, 0, &curve);
+        let parameters = DomainParameters::new(curve.clone(), g.clone(), BigUint::from(2u32), BigUint::from(22u32)).validate().unwrap();
+        assert!(parameters.fixed_base.is_none());
+        assert_eq!(parameters.generator_mul(3).unwrap(), g);
+    }
+
+    #[test]
+    fn test_random_secret() {
+        let curve = EllipticCurve::new(0, 6, 43).unwrap();
+        let parameters = DomainParameters::new(curve.clone(), point(13, 15, &curve), BigUint::from(13u32), BigUint::from(3u32))
+            .validate()
+            .unwrap();
+        let mut rng = rand::thread_rng();
+        let secrets: std::collections::HashSet<u64> = (0..1000)
+            .map(|_| parameters.random_secret(&mut rng).to_u64().unwrap())
+            .collect();
+        assert_eq!(secrets, (1..13).collect());
+    }
+
+    #[test]
+    fn test_validate_long_form() {
+        // 101 points over F_107, the curve is in long form so there is no table and generator_mul uses the ladder
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 107).unwrap();
+        let g = point(4, 32, &curve);
+        let parameters = DomainParameters::new(curve, g.clone(), BigUint::from(101u32), BigUint::one()).validate().unwrap();
+        assert!(parameters.fixed_base.is_none());
+        for k in 0..105 {
+            assert_eq!(parameters.generator_mul(k).unwrap(), g.scalar_mul(k).unwrap());
+        }
+        // the shared key of diffie hellman
+        let (a, b) = (37, 88);
+        assert_eq!(
+            parameters.generator_mul(a).unwrap().scalar_mul_ladder(b, parameters.order()).unwrap(),
+            parameters.generator_mul(b).unwrap().scalar_mul_ladder(a, parameters.order()).unwrap()
+        );
+
+        // characteristic 3 has no short form for the ladder
+        let curve = EllipticCurve::new_long([0, 1, 0, 0, 1], 3).unwrap();
+        let parameters = DomainParameters::new(curve.clone(), point(0, 1, &curve), BigUint::from(3u32), BigUint::from(2u32));
+        assert_eq!(parameters.validate().unwrap_err(), "Domain parameters need a characteristic bigger than 3, got F_3");
+    }
+
+    #[test]
+    fn test_validate_rejects_generator() {
+        let curve = EllipticCurve::new(0, 6, 43).unwrap();
+        // g = (9, 2) generates the whole group of 39 points, which is not a prime order
+        let g = point(9, 2, &curve);
+        let parameters = DomainParameters::new(curve.clone(), g.clone(), BigUint::from(39u32), BigUint::one());
+        assert_eq!(parameters.validate().unwrap_err(), "Order 39 of the generator is not prime");
+        // and it doesn't have order 13
+        let parameters = DomainParameters::new(curve.clone(), g.clone(), BigUint::from(13u32), BigUint::from(3u32));
+        assert!(parameters.validate().unwrap_err().contains("does not have order 13"));
+
+        // wrong cofactor
+        let parameters = DomainParameters::new(curve.clone(), point(13, 15, &curve), BigUint::from(13u32), BigUint::one());
+        assert!(parameters.validate().unwrap_err().starts_with("Cofactor 1 times order 13"));
+
+        // point at infinity
+        let infinity = Point::new(None, None, curve.clone()).unwrap();
+        let parameters = DomainParameters::new(curve.clone(), infinity, BigUint::from(13u32), BigUint::from(3u32));
+        assert!(parameters.validate().is_err());
+
+        // point of another curve
+        let other = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let parameters = DomainParameters::new(curve, point(379, 1011, &other), BigUint::from(1039u32), BigUint::one());
+        assert!(parameters.validate().unwrap_err().contains("does not belong to"));
+    }
+}
@@ -1,76 +1,687 @@
 use std::{fmt::{Display, Formatter, self}};
-use crate::finite_field_element::FiniteFieldElement;
+use crate::big_int::{BigInt, BigUint};
+use crate::field::{Field, FiniteField};
+use crate::factorization::factorize;
+use crate::finite_field_element::{FiniteFieldElement, PrimeField};
+use crate::point::Point;
+use crate::primality::is_prime;
+use crate::{mestre, schoof};
 
+// up to this number of points the group structure takes the order of every point
+const GROUP_STRUCTURE_ALL_POINTS: u32 = 1000;
+
+// random points whose orders give the exponent of bigger groups, a point misses the full power of a
+// prime q of the exponent with probability at most 1/q so the result is wrong with probability below 2^-32
+const GROUP_STRUCTURE_RANDOM_POINTS: usize = 32;
+
+// E(F_p) is isomorphic to Z/n1 x Z/n2 with n1 dividing n2 and p - 1, n2 is the exponent of the group
+// (the biggest order of a point) and the group is cyclic when n1 = 1
 #[derive(Debug, PartialEq, Clone)]
+pub struct GroupStructure {
+    pub n1: BigUint,
+    pub n2: BigUint,
+}
 
+impl GroupStructure {
+    pub fn is_cyclic(&self) -> bool {
+        self.n1.is_one()
+    }
+}
 
-pub struct EllipticCurve {
-    pub a: f64,
-    pub b: f64,
+impl Display for GroupStructure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_cyclic() {
+            write!(f, "Z/{}", self.n2)
+        } else {
+            write!(f, "Z/{} x Z/{}", self.n1, self.n2)
+        }
+    }
+}
+
+// curve in long weierstrass form
+// y^2 + a1 xy + a3 y = x^3 + a2 x^2 + a4 x + a6
+// the short form y^2 = x^3 + ax + b is the case a1 = a2 = a3 = 0, a4 = a, a6 = b, which can't
+// describe a non singular curve in characteristic 2 and 3
+#[derive(Debug, PartialEq, Clone)]
+pub struct EllipticCurve<F: Field = FiniteFieldElement> {
+    pub a1: F,
+    pub a2: F,
+    pub a3: F,
+    pub a4: F,
+    pub a6: F,
+    pub field: F::Parent,
 }
 
 impl EllipticCurve {
-    pub fn new(a: f64, b: f64) -> EllipticCurve {
-        EllipticCurve {
-            a,
-            b,
-        }
+    // curve y^2 = x^3 + ax + b over F_p, the coefficients are reduced modulo p.
+    // fails when the modulus is not a prime or the curve is singular
+    pub fn new<A: Into<BigInt>, B: Into<BigInt>, M: Into<BigInt>>(a: A, b: B, modulus: M) -> Result<EllipticCurve, String> {
+        let field = PrimeField::new(modulus)?;
+        EllipticCurve::with_field(field.element(0), field.element(0), field.element(0), field.element(a), field.element(b), field)
     }
 
-    pub fn eq(&self, other: &EllipticCurve) -> bool {
-        self.a == other.a && self.b == other.b
+    // curve in long weierstrass form with coefficients [a1, a2, a3, a4, a6] over F_p
+    pub fn new_long<C: Into<BigInt>, M: Into<BigInt>>(coefficients: [C; 5], modulus: M) -> Result<EllipticCurve, String> {
+        let field = PrimeField::new(modulus)?;
+        let [a1, a2, a3, a4, a6] = coefficients.map(|c| field.element(c));
+        EllipticCurve::with_field(a1, a2, a3, a4, a6, field)
     }
 
-    pub fn check_point(&self, x: FiniteFieldElement, y: FiniteFieldElement) -> bool {
-        let first = x.pow(3).expect("Error in check_point first argument");
-        let second = x.mul(&FiniteFieldElement::new(self.a as i32, x.modulus)).expect("Error in check_point second argument");
-        let third = FiniteFieldElement::new(self.b as i32, x.modulus);
-        let y_squared = first.add(&second).expect("Error in check_point first argument").add(&third);
-        y_squared == y.pow(2)
+    // number of points with mestre's baby-step giant-step method, O(p^1/4) group operations so it is
+    // practical up to about 60 bit primes, see mestre::order
+    pub fn order_bsgs(&self) -> Result<BigUint, String> {
+        mestre::order(self)
     }
-}
 
-impl Display for EllipticCurve {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let a = if self.a < 0.0 {
-            format!("- {}", self.a.abs())
+    // there is a point with this x coordinate iff 4x^3 + b2 x^2 + 2b4 x + b6 is a square, which the legendre
+    // symbol answers without computing the square root. The modulus must be an odd prime
+    pub fn has_x_coordinate(&self, x: &FiniteFieldElement) -> Result<bool, String> {
+        Ok(self.completed_square(x).legendre()? != -1)
+    }
+
+    // number of points of the curve over F_p (including infinity) in O(p) legendre symbols:
+    // each x gives 1 + (4x^3 + b2 x^2 + 2b4 x + b6 / p) points, so #E = p + 1 + sum over x of the symbols
+    pub fn count_points(&self) -> Result<BigUint, String> {
+        let mut count = BigInt::from(&self.field.modulus + &BigUint::one());
+        let mut x = self.field.element(0);
+        loop {
+            count = &count + &BigInt::from(self.completed_square(&x).legendre()?);
+            x += x.one();
+            if x.is_zero() {
+                break;
+            }
+        }
+        BigUint::try_from(count)
+    }
+
+    // structure of the group of points as Z/n1 x Z/n2. The exponent n2 is the lcm of the orders of the
+    // points, every point on small curves and random points otherwise, and n1 = #E / n2
+    pub fn group_structure(&self) -> Result<GroupStructure, String> {
+        let n = self.order()?;
+        let factors = factorize(&n)?;
+        let mut exponent = BigUint::one();
+        let mut update = |point: Point| -> Result<(), String> {
+            let order = point.order_with_factors(&n, &factors)?;
+            exponent = &(&exponent / &exponent.gcd(&order)) * &order;
+            Ok(())
+        };
+        if n <= BigUint::from(GROUP_STRUCTURE_ALL_POINTS) {
+            for point in self.points() {
+                update(point)?;
+            }
         } else {
-            format!("+ {}", self.a)
+            let mut rng = rand::thread_rng();
+            for _ in 0..GROUP_STRUCTURE_RANDOM_POINTS {
+                update(Point::random(self, &mut rng)?)?;
+            }
+        }
+        let n1 = &n / &exponent;
+        // n1 divides n2 and p - 1 (the n1 torsion is full and the weil pairing needs the n1-th roots of unity)
+        let p_minus_one = &self.field.modulus - &BigUint::one();
+        if !(&exponent % &n1).is_zero() || !(&p_minus_one % &n1).is_zero() {
+            return Err(format!("Error in group_structure inconsistent exponent {} for {} points", exponent, n));
+        }
+        Ok(GroupStructure { n1, n2: exponent })
+    }
+}
+
+impl<F: Field> EllipticCurve<F> {
+    fn with_field(a1: F, a2: F, a3: F, a4: F, a6: F, field: F::Parent) -> Result<EllipticCurve<F>, String> {
+        let curve = EllipticCurve {
+            a1,
+            a2,
+            a3,
+            a4,
+            a6,
+            field,
         };
-        let b = if self.b < 0.0 {
-            format!("- {}", self.b.abs())
+        curve.check_non_singular()?;
+        Ok(curve)
+    }
+
+    // short curve y^2 = x^3 + ax + b with coefficients of any field implementation, defined over the
+    // field a and b belong to
+    pub fn from_coefficients(a: F, b: F) -> Result<EllipticCurve<F>, String> {
+        EllipticCurve::from_long_coefficients(a.zero(), a.zero(), a.zero(), a, b)
+    }
+
+    pub fn from_long_coefficients(a1: F, a2: F, a3: F, a4: F, a6: F) -> Result<EllipticCurve<F>, String> {
+        let field = a1.field();
+        if [&a2, &a3, &a4, &a6].iter().any(|c| c.field() != field) {
+            return Err("Modulus mismatch".to_string());
+        }
+        // elements built without checks (FiniteFieldElement::new) may have a composite modulus
+        if !is_prime(&field.characteristic()) {
+            return Err(format!("Modulus {} is not prime", field.characteristic()));
+        }
+        EllipticCurve::with_field(a1, a2, a3, a4, a6, field)
+    }
+
+    // the same curve with the coefficients mapped to another representation of the field
+    pub(crate) fn map_coefficients<G: Field>(&self, f: impl Fn(&F) -> Result<G, String>) -> Result<EllipticCurve<G>, String> {
+        let a1 = f(&self.a1)?;
+        Ok(EllipticCurve {
+            field: a1.field(),
+            a1,
+            a2: f(&self.a2)?,
+            a3: f(&self.a3)?,
+            a4: f(&self.a4)?,
+            a6: f(&self.a6)?,
+        })
+    }
+
+    // completing the square with n = 2y + a1 x + a3 the equation becomes n^2 = 4x^3 + b2 x^2 + 2b4 x + b6
+    pub(crate) fn completed_square(&self, x: &F) -> F {
+        x.integer(4) * x.square() * x + self.b2() * x.square() + x.integer(2) * self.b4() * x + self.b6()
+    }
+
+    // affine points with this x coordinate, at most two.
+    // in odd characteristic (2y + a1 x + a3)^2 = 4x^3 + b2 x^2 + 2b4 x + b6 is solved with one square root.
+    // in characteristic 2 the equation is y^2 + by = c with b = a1 x + a3 and c = x^3 + a2 x^2 + a4 x + a6:
+    // for b = 0 the only y is the square root of c, otherwise y = bz with z^2 + z = c / b^2
+    pub fn points_with_x(&self, x: &F) -> Vec<Point<F>> {
+        let ys = if self.field.characteristic() == BigUint::from(2u32) {
+            let b = self.a1.clone() * x + &self.a3;
+            let c = self.rhs(x);
+            if b.is_zero() {
+                c.square_root().into_iter().collect()
+            } else {
+                let d = c.div(&b.square()).expect("Error in EllipticCurve::points_with_x division by b^2");
+                match solve_quadratic(&d) {
+                    Some(z) => vec![b.clone() * &z, b * (z + x.one())],
+                    None => vec![],
+                }
+            }
         } else {
-            format!("+ {}", self.b)
+            let roots = match self.completed_square(x).square_root() {
+                Some(root) if root.is_zero() => vec![root],
+                Some(root) => vec![root.clone(), -root],
+                None => vec![],
+            };
+            let half = x.integer(2).inv().expect("Error in EllipticCurve::points_with_x inverse of 2");
+            roots.into_iter().map(|root| (root - self.a1.clone() * x - &self.a3) * &half).collect()
         };
-        write!(f, "Elliptic Curve: y^2 = x^3 {}x {}", a, b)
+        ys.into_iter()
+            .map(|y| Point::new(Some(x.clone()), Some(y), self.clone()).expect("Error in EllipticCurve::points_with_x point not on the curve"))
+            .collect()
+    }
+
+    // every point of the curve, infinity first and then the affine points by x coordinate in the order
+    // of Field::successor. Each x takes one square root instead of testing the q possible y
+    pub fn points(&self) -> impl Iterator<Item = Point<F>> + '_ {
+        let infinity = Point::new(None, None, self.clone()).expect("Error in EllipticCurve::points infinity");
+        let xs = std::iter::successors(Some(self.a1.zero()), |x| x.successor());
+        std::iter::once(infinity).chain(xs.flat_map(move |x| self.points_with_x(&x)))
+    }
+
+    pub fn is_short(&self) -> bool {
+        self.a1.is_zero() && self.a2.is_zero() && self.a3.is_zero()
+    }
+
+    // b2 = a1^2 + 4a2
+    pub fn b2(&self) -> F {
+        self.a1.square() + self.a1.integer(4) * &self.a2
+    }
+
+    // b4 = 2a4 + a1 a3
+    pub fn b4(&self) -> F {
+        self.a1.integer(2) * &self.a4 + self.a1.clone() * &self.a3
+    }
+
+    // b6 = a3^2 + 4a6
+    pub fn b6(&self) -> F {
+        self.a3.square() + self.a1.integer(4) * &self.a6
+    }
+
+    // b8 = a1^2 a6 + 4a2 a6 - a1 a3 a4 + a2 a3^2 - a4^2
+    pub fn b8(&self) -> F {
+        self.a1.square() * &self.a6 + self.a1.integer(4) * &self.a2 * &self.a6 - self.a1.clone() * &self.a3 * &self.a4
+            + self.a2.clone() * self.a3.square() - self.a4.square()
+    }
+
+    // c4 = b2^2 - 24b4
+    pub fn c4(&self) -> F {
+        self.b2().square() - self.a1.integer(24) * self.b4()
+    }
+
+    // c6 = -b2^3 + 36b2 b4 - 216b6
+    pub fn c6(&self) -> F {
+        let b2 = self.b2();
+        -(b2.square() * &b2) + self.a1.integer(36) * &b2 * self.b4() - self.a1.integer(216) * self.b6()
+    }
+
+    // discriminant = -b2^2 b8 - 8b4^3 - 27b6^2 + 9b2 b4 b6, for the short form -16(4a^3 + 27b^2)
+    pub fn discriminant(&self) -> F {
+        let (b2, b4, b6) = (self.b2(), self.b4(), self.b6());
+        -(b2.square() * self.b8()) - self.a1.integer(8) * b4.square() * &b4 - self.a1.integer(27) * b6.square()
+            + self.a1.integer(9) * &b2 * &b4 * &b6
+    }
+
+    // j = c4^3 / discriminant, for the short form 1728 4a^3 / (4a^3 + 27b^2)
+    // two curves over the algebraic closure are isomorphic iff they have the same j-invariant
+    pub fn j_invariant(&self) -> Result<F, String> {
+        let c4 = self.c4();
+        (c4.square() * &c4).div(&self.discriminant())
+    }
+
+    // a curve with discriminant 0 has a node or a cusp and its points don't form a group
+    fn check_non_singular(&self) -> Result<(), String> {
+        if self.discriminant().is_zero() {
+            return Err(format!("Singular curve: discriminant is 0 over {}", self.field));
+        }
+        Ok(())
+    }
+
+    // isomorphic curve y^2 = x^3 - 27c4 x - 54c6, points are mapped with short_weierstrass_point.
+    // the change of variables divides by 2 and 3 so it fails in characteristic 2 and 3
+    pub fn to_short_weierstrass(&self) -> Result<EllipticCurve<F>, String> {
+        if self.field.characteristic() <= BigUint::from(3u32) {
+            return Err(format!("There is no short weierstrass form over {}", self.field));
+        }
+        let zero = self.a1.zero();
+        EllipticCurve::with_field(
+            zero.clone(),
+            zero.clone(),
+            zero,
+            self.a1.integer(-27) * self.c4(),
+            self.a1.integer(-54) * self.c6(),
+            self.field.clone(),
+        )
+    }
+
+    // (x, y) -> (36x + 3b2, 108(2y + a1 x + a3)), image of a point of self in to_short_weierstrass
+    pub fn short_weierstrass_point(&self, x: &F, y: &F) -> (F, F) {
+        let short_x = x.integer(36) * x + x.integer(3) * self.b2();
+        let short_y = x.integer(108) * (x.integer(2) * y + self.a1.clone() * x + &self.a3);
+        (short_x, short_y)
+    }
+
+    // (x, y) -> ((x - 3b2) / 36, (y / 108 - a1 x' - a3) / 2), inverse of short_weierstrass_point
+    pub fn long_weierstrass_point(&self, x: &F, y: &F) -> Result<(F, F), String> {
+        let long_x = (x.clone() - x.integer(3) * self.b2()).div(&x.integer(36))?;
+        let long_y = (y.div(&x.integer(108))? - self.a1.clone() * &long_x - &self.a3).div(&x.integer(2))?;
+        Ok((long_x, long_y))
+    }
+
+    // number of points over F_q including infinity, with schoof's algorithm on the isomorphic short
+    // weierstrass curve. In characteristic 2 and 3 the points are enumerated
+    pub fn order(&self) -> Result<BigUint, String> {
+        if self.field.characteristic() <= BigUint::from(3u32) {
+            return Ok(BigUint::from(self.points().count() as u64));
+        }
+        if self.is_short() {
+            schoof::order(self)
+        } else {
+            schoof::order(&self.to_short_weierstrass()?)
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn eq(&self, other: &EllipticCurve<F>) -> bool {
+        self.a1 == other.a1 && self.a2 == other.a2 && self.a3 == other.a3 && self.a4 == other.a4 && self.a6 == other.a6
+            && self.field == other.field
+    }
+
+    pub fn check_point(&self, x: F, y: F) -> bool {
+        // both coordinates must belong to the field of the curve
+        if x.field() != self.field || y.field() != self.field {
+            return false;
+        }
+        // y^2 + a1 xy + a3 y = x^3 + a2 x^2 + a4 x + a6
+        y.square() + self.a1.clone() * &x * &y + self.a3.clone() * &y == self.rhs(&x)
+    }
+
+    // y coordinate of -(x, y), the other point on the vertical line: -y - a1 x - a3
+    pub fn negate_y(&self, x: &F, y: &F) -> F {
+        -y.clone() - self.a1.clone() * x - &self.a3
+    }
+
+    // x^3 + a2 x^2 + a4 x + a6
+    fn rhs(&self, x: &F) -> F {
+        x.square() * x + self.a2.clone() * x.square() + self.a4.clone() * x + &self.a6
+    }
+}
+
+// a solution z of z^2 + z = d in a field of size q = 2^m, None when there is none (the trace of d is 1).
+// with t of trace Tr(t) = t + t^2 + t^4 + ... + t^(2^(m-1)) = 1 the solution is
+// z = sum for i < m of (t^(2^(i+1)) + ... + t^(2^(m-1))) d^(2^i), since z^2 + z = t Tr(d) + d
+fn solve_quadratic<F: Field>(d: &F) -> Option<F> {
+    let m = d.field().size().bits() - 1;
+    // the first m powers x, x^2, x^4, ... of an element
+    let frobenius = |x: &F| -> Vec<F> {
+        std::iter::successors(Some(x.clone()), |x| Some(x.square())).take(m).collect()
+    };
+    let trace = |x: &F| frobenius(x).into_iter().fold(x.zero(), |sum, power| sum + power);
+    let t = std::iter::successors(Some(d.zero()), |t| t.successor())
+        .find(|t| trace(t) == d.one())
+        .expect("Error in solve_quadratic there is no element of trace 1");
+    let t_powers = frobenius(&t);
+    let d_powers = frobenius(d);
+    let mut coefficient = d.zero();
+    let mut z = d.zero();
+    for i in (0..m).rev() {
+        z = z + coefficient.clone() * &d_powers[i];
+        coefficient = coefficient + &t_powers[i];
+    }
+    (z.square() + &z == *d).then_some(z)
+}
+
+impl<F: Field> Display for EllipticCurve<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_short() {
+            return write!(
+                f,
+                "Elliptic Curve: y^2 = x^3 + {:#}x + {:#} over {}",
+                self.a4,
+                self.a6,
+                self.field
+            );
+        }
+        write!(
+            f,
+            "Elliptic Curve: y^2 + {:#}xy + {:#}y = x^3 + {:#}x^2 + {:#}x + {:#} over {}",
+            self.a1,
+            self.a3,
+            self.a2,
+            self.a4,
+            self.a6,
+            self.field
+        )
     }
 }
 
 // testing module for Elliptic curve
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
+    use crate::extension_field::{ExtensionField, ExtensionFieldElement};
+    use crate::prime_field::F1021;
 
     #[test]
     fn test_new() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
-        assert_eq!(curve.a, -3.0);
-        assert_eq!(curve.b, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        assert_eq!(curve.a4, FiniteFieldElement::new(1018, 1021));
+        assert_eq!(curve.a6, FiniteFieldElement::new(1018, 1021));
+        assert!(curve.is_short());
+        assert_eq!(curve.field.modulus, BigUint::from(1021u32));
     }
 
     #[test]
+    fn test_display() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        assert_eq!(curve.to_string(), "Elliptic Curve: y^2 = x^3 + 1018x + 1018 over F_1021");
+        let curve = EllipticCurve::new(905 + 1021, -921, 1021).unwrap();
+        assert_eq!(curve.to_string(), "Elliptic Curve: y^2 = x^3 + 905x + 100 over F_1021");
+    }
+
+    #[test]
+    fn test_big_prime_coefficients() {
+        // P-256, b doesn't fit in any primitive type and a = -3 is stored as p - 3
+        let p: BigUint = "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap();
+        let b: BigUint = "0x5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b".parse().unwrap();
+        let gx: BigUint = "0x6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296".parse().unwrap();
+        let gy: BigUint = "0x4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5".parse().unwrap();
+        let curve = EllipticCurve::new(-3, b.clone(), p.clone()).unwrap();
+        assert_eq!(curve.a4.value, &p - &BigUint::from(3u32));
+        assert_eq!(curve.a6.value, b);
+        assert!(curve.check_point(FiniteFieldElement::new(gx.clone(), p.clone()), FiniteFieldElement::new(gy.clone(), p.clone())));
+        assert!(!curve.check_point(FiniteFieldElement::new(gx, p.clone()), FiniteFieldElement::new(&gy + &BigUint::one(), p)));
+    }
+
+    #[test]
+    fn test_from_coefficients() {
+        let curve = EllipticCurve::from_coefficients(F1021::new(-3), F1021::new(-3)).unwrap();
+        assert_eq!(curve.field, PrimeField::new(1021).unwrap());
+        assert!(curve.check_point(F1021::new(379), F1021::new(1011)));
+        let mismatch = EllipticCurve::from_coefficients(FiniteFieldElement::new(1, 1021), FiniteFieldElement::new(1, 43));
+        assert_eq!(mismatch.unwrap_err(), "Modulus mismatch");
+        assert!(EllipticCurve::from_coefficients(FiniteFieldElement::new(1, 15), FiniteFieldElement::new(1, 15)).is_err());
+    }
+
+    #[test]
+    fn test_new_invalid_modulus() {
+        assert_eq!(EllipticCurve::new(-3, -3, 1023).unwrap_err(), "Modulus 1023 is not prime");
+        assert_eq!(EllipticCurve::new(-3, -3, 0).unwrap_err(), "Modulus must be greater than 1, got 0");
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_eq() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
-        let other = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let other = EllipticCurve::new(-3, -3, 1021).unwrap();
         assert_eq!(curve.eq(&other), true);
+        let other_field = EllipticCurve::new(-3, -3, 1019).unwrap();
+        assert!(!curve.eq(&other_field));
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_check_point() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
         let x = FiniteFieldElement::new(379, 1021);
         let y = FiniteFieldElement::new(1011, 1021);
         assert_eq!(curve.check_point(x, y), true);
+        // same values in another field
+        let x = FiniteFieldElement::new(379, 1019);
+        let y = FiniteFieldElement::new(1011, 1019);
+        assert!(!curve.check_point(x, y));
+    }
+
+    #[test]
+    fn test_singular_curves() {
+        // y^2 = x^3 has a cusp and y^2 = x^3 - 3x + 2 = (x - 1)^2 (x + 2) a node
+        for (a, b) in [(0, 0), (-3, 2)] {
+            assert_eq!(EllipticCurve::new(a, b, 1021).unwrap_err(), "Singular curve: discriminant is 0 over F_1021");
+        }
+        // 4 + 27 = 31 is zero only modulo 31
+        assert!(EllipticCurve::new(1, 1, 31).is_err());
+        assert!(EllipticCurve::new(1, 1, 37).is_ok());
+        assert!(EllipticCurve::from_coefficients(F1021::new(-3), F1021::new(2)).is_err());
+        // every short weierstrass curve is singular in characteristic 2
+        assert!(EllipticCurve::new(1, 1, 2).is_err());
+    }
+
+    #[test]
+    fn test_discriminant_j_invariant() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        // -16 (4 (-27) + 27 9) = -2160
+        assert_eq!(curve.discriminant(), FiniteFieldElement::new(-2160, 1021));
+        // 1728 4 (-27) / 135 = -1382.4 = -6912 / 5
+        assert_eq!(curve.j_invariant().unwrap() * FiniteFieldElement::new(5, 1021), FiniteFieldElement::new(-6912, 1021));
+
+        // y^2 = x^3 + b has j = 0 and y^2 = x^3 + ax has j = 1728
+        assert!(EllipticCurve::new(0, 6, 43).unwrap().j_invariant().unwrap().is_zero());
+        assert_eq!(EllipticCurve::new(-1, 0, 43).unwrap().j_invariant().unwrap(), FiniteFieldElement::new(1728, 43));
+
+        // twists (u^4 a, u^6 b) are isomorphic over the closure so they share the j-invariant
+        let twist = EllipticCurve::new(-3 * 16, -3 * 64, 1021).unwrap();
+        assert!(!twist.eq(&curve));
+        assert_eq!(twist.j_invariant().unwrap(), curve.j_invariant().unwrap());
+    }
+
+    #[test]
+    fn test_long_form_invariants() {
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 1021).unwrap();
+        assert!(!curve.is_short());
+        // relations between the invariants: 4b8 = b2 b6 - b4^2 and 1728 discriminant = c4^3 - c6^2
+        assert_eq!(curve.b8() * FiniteFieldElement::new(4, 1021), curve.b2() * curve.b6() - curve.b4().square());
+        let c4 = curve.c4();
+        assert_eq!(curve.discriminant() * FiniteFieldElement::new(1728, 1021), c4.square() * &c4 - curve.c6().square());
+        // for the short form b2 = 0, b4 = 2a, b6 = 4b and c4 = -48a
+        let short = EllipticCurve::new(-3, -3, 1021).unwrap();
+        assert!(short.b2().is_zero());
+        assert_eq!(short.b4(), FiniteFieldElement::new(-6, 1021));
+        assert_eq!(short.b6(), FiniteFieldElement::new(-12, 1021));
+        assert_eq!(short.c4(), FiniteFieldElement::new(144, 1021));
+        assert_eq!(
+            curve.to_string(),
+            "Elliptic Curve: y^2 + 1xy + 3y = x^3 + 1020x^2 + 7x + 1016 over F_1021"
+        );
+    }
+
+    #[test]
+    fn test_small_characteristic() {
+        // y^2 + xy = x^3 + 1 over F_2 and y^2 = x^3 + x^2 + 1 over F_3, both with discriminant -1
+        let curve = EllipticCurve::new_long([1, 0, 0, 0, 1], 2).unwrap();
+        assert_eq!(curve.discriminant(), FiniteFieldElement::new(-1, 2));
+        assert!(curve.check_point(FiniteFieldElement::new(1, 2), FiniteFieldElement::new(0, 2)));
+        assert_eq!(curve.to_short_weierstrass().unwrap_err(), "There is no short weierstrass form over F_2");
+        let curve = EllipticCurve::new_long([0, 1, 0, 0, 1], 3).unwrap();
+        assert_eq!(curve.discriminant(), FiniteFieldElement::new(-1, 3));
+        assert!(curve.to_short_weierstrass().is_err());
+        // y^2 + y = x^3 is supersingular with j = 0 in characteristic 2
+        let curve = EllipticCurve::new_long([0, 0, 1, 0, 0], 2).unwrap();
+        assert!(curve.j_invariant().unwrap().is_zero());
+        assert!(EllipticCurve::new_long([0, 0, 0, 1, 1], 2).is_err());
+    }
+
+    #[test]
+    fn test_to_short_weierstrass() {
+        for p in [43, 97, 1021] {
+            let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], p).unwrap();
+            let short = curve.to_short_weierstrass().unwrap();
+            assert!(short.is_short());
+            assert_eq!(short.j_invariant().unwrap(), curve.j_invariant().unwrap());
+            // isomorphic curves have the same number of points
+            assert_eq!(short.count_points().unwrap(), curve.count_points().unwrap());
+            // long_weierstrass_point undoes short_weierstrass_point
+            for point in curve.points().skip(1) {
+                let (x, y) = (point.x.unwrap(), point.y.unwrap());
+                let (short_x, short_y) = curve.short_weierstrass_point(&x, &y);
+                assert!(short.check_point(short_x.clone(), short_y.clone()));
+                assert_eq!(curve.long_weierstrass_point(&short_x, &short_y).unwrap(), (x, y));
+            }
+        }
+        // a short curve is mapped to a twist by 6^2, with the same j-invariant
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let short = curve.to_short_weierstrass().unwrap();
+        assert_eq!(short.a4, curve.a4.clone() * FiniteFieldElement::new(6i64.pow(4), 1021));
+        assert_eq!(short.a6, curve.a6.clone() * FiniteFieldElement::new(6i64.pow(6), 1021));
+    }
+
+    #[test]
+    fn test_count_points_long_form() {
+        // compared against the O(p^2) search
+        for coefficients in [[1, -1, 3, 7, -5], [1, 0, 1, 0, 2], [0, 3, 0, 1, 1]] {
+            let curve = EllipticCurve::new_long(coefficients, 43).unwrap();
+            let mut naive = 1u32;
+            for x in 0..43 {
+                for y in 0..43 {
+                    if curve.check_point(FiniteFieldElement::new(x, 43), FiniteFieldElement::new(y, 43)) {
+                        naive += 1;
+                    }
+                }
+            }
+            assert_eq!(curve.count_points().unwrap(), BigUint::from(naive));
+        }
+    }
+
+    #[test]
+    fn test_order() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        assert_eq!(curve.order().unwrap(), BigUint::from(1039u32));
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap();
+        assert_eq!(curve.order().unwrap(), curve.count_points().unwrap());
+        // y^2 + xy = x^3 + 1 over F_2 has (0, 1), (1, 0) and (1, 1)
+        assert_eq!(EllipticCurve::new_long([1, 0, 0, 0, 1], 2).unwrap().order().unwrap(), BigUint::from(4u32));
+        // y^2 = x^3 + x^2 + 1 over F_3 has (0, 1), (0, 2), (1, 0), (2, 1) and (2, 2)
+        assert_eq!(EllipticCurve::new_long([0, 1, 0, 0, 1], 3).unwrap().order().unwrap(), BigUint::from(6u32));
+    }
+
+    #[test]
+    fn test_order_over_extension_fields() {
+        // y^2 = x^3 + x + 4 has 9 points over F_11, trace 3, and 11^2 + 1 - (9 - 2 11) = 135 over F_(11^2)
+        let field = Arc::new(ExtensionField::new(&PrimeField::new(11).unwrap(), vec![1, 0, 1]).unwrap());
+        let curve = EllipticCurve::from_coefficients(
+            ExtensionFieldElement::new(vec![1], &field), ExtensionFieldElement::new(vec![4], &field)
+        ).unwrap();
+        assert_eq!(curve.field, *field);
+        assert_eq!(curve.to_string(), "Elliptic Curve: y^2 = x^3 + 1x + 4 over F_11[a]/(a^2 + 1)");
+        assert_eq!(curve.order().unwrap(), BigUint::from(135u32));
+        // y^2 + xy = x^3 + 1 has 4 points over F_2 and 16 over F_16
+        let field = Arc::new(ExtensionField::new(&PrimeField::new(2).unwrap(), vec![1, 1, 0, 0, 1]).unwrap());
+        let e = |c: i64| ExtensionFieldElement::new(vec![c], &field);
+        let curve = EllipticCurve::from_long_coefficients(e(1), e(0), e(0), e(0), e(1)).unwrap();
+        assert_eq!(curve.order().unwrap(), BigUint::from(16u32));
+        assert!(curve.to_short_weierstrass().is_err());
+    }
+
+    #[test]
+    fn test_points() {
+        for curve in [
+            EllipticCurve::new(-3, -3, 1021).unwrap(),
+            EllipticCurve::new(0, 6, 43).unwrap(),
+            EllipticCurve::new(-1, 0, 43).unwrap(),
+            EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap(),
+            EllipticCurve::new_long([0, 1, 0, 0, 1], 3).unwrap(),
+            EllipticCurve::new_long([1, 0, 0, 0, 1], 2).unwrap(),
+        ] {
+            let points: Vec<Point> = curve.points().collect();
+            assert_eq!(BigUint::from(points.len()), curve.order().unwrap(), "{}", curve);
+            assert!(points[0].is_infinity());
+            for (i, point) in points.iter().enumerate().skip(1) {
+                assert!(curve.check_point(point.x.clone().unwrap(), point.y.clone().unwrap()));
+                assert!(!points[..i].contains(point));
+            }
+        }
+        // y^2 = x^3 - x has the three points with y = 0 once each
+        let curve = EllipticCurve::new(-1, 0, 43).unwrap();
+        assert_eq!(curve.points_with_x(&FiniteFieldElement::new(1, 43)).len(), 1);
+        assert_eq!(curve.points().filter(|point| point.y.as_ref().is_some_and(|y| y.is_zero())).count(), 3);
+    }
+
+    #[test]
+    fn test_has_x_coordinate() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let squares: Vec<FiniteFieldElement> = (0..1021).map(|y| FiniteFieldElement::new(y, 1021).square()).collect();
+        for x in 0..1021 {
+            let x = FiniteFieldElement::new(x, 1021);
+            let exists = squares.contains(&curve.rhs(&x));
+            assert_eq!(curve.has_x_coordinate(&x).unwrap(), exists);
+        }
+    }
+
+    #[test]
+    fn test_group_structure() {
+        // prime order, cyclic
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let structure = curve.group_structure().unwrap();
+        assert_eq!(structure, GroupStructure { n1: BigUint::one(), n2: BigUint::from(1039u32) });
+        assert_eq!(structure.to_string(), "Z/1039");
+        // the curve of the diffie hellman exercise has 39 = 3 * 13 points, also cyclic
+        assert_eq!(EllipticCurve::new(0, 6, 43).unwrap().group_structure().unwrap().to_string(), "Z/39");
+        // y^2 = x^3 - x = x(x - 1)(x + 1) has the three points of order 2 and p + 1 points for p = 3 mod 4
+        let structure = EllipticCurve::new(-1, 0, 43).unwrap().group_structure().unwrap();
+        assert!(!structure.is_cyclic());
+        assert_eq!(structure.to_string(), "Z/2 x Z/22");
+        // with random points, 10008 = 2^3 * 3^2 * 139 points
+        let structure = EllipticCurve::new(-1, 0, 10007).unwrap().group_structure().unwrap();
+        assert_eq!(structure, GroupStructure { n1: BigUint::from(2u32), n2: BigUint::from(5004u32) });
+        // y^2 + xy = x^3 + 1 over F_2 has the point (1, 0) of order 4
+        assert_eq!(EllipticCurve::new_long([1, 0, 0, 0, 1], 2).unwrap().group_structure().unwrap().to_string(), "Z/4");
+    }
+
+    #[test]
+    fn test_count_points() {
+        // count of the O(p^2) search over all (x, y) pairs in main.rs
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        assert_eq!(curve.count_points().unwrap(), BigUint::from(1039u32));
+
+        // compared against the O(p^2) search on small fields
+        for (a, b, p) in [(0, 6, 43), (-1, 0, 43), (2, 3, 97), (-3, 7, 101)] {
+            let curve = EllipticCurve::new(a, b, p).unwrap();
+            let mut naive = 1;
+            for x in 0..p {
+                for y in 0..p {
+                    if curve.check_point(FiniteFieldElement::new(x, p), FiniteFieldElement::new(y, p)) {
+                        naive += 1;
+                    }
+                }
+            }
+            assert_eq!(curve.count_points().unwrap(), BigUint::from(naive as u32));
+        }
     }
 
 
@@ -0,0 +1,420 @@
+use std::fmt::{Display, Formatter, self};
+use std::sync::Arc;
+
+use crate::big_int::{BigInt, BigUint};
+use crate::field::{Field, FiniteField, impl_field_operators, tonelli_shanks};
+use crate::finite_field_element::{FiniteFieldElement, PrimeField};
+use crate::polynomial::Polynomial;
+
+// extension F_(p^k) = F_p[a] / (f) of a prime field by a monic irreducible polynomial f of degree k,
+// its elements are the polynomials in a of degree below k. With p = 2 these are the binary fields F_(2^k)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionField {
+    pub base: PrimeField,
+    modulus: Polynomial<FiniteFieldElement>,
+    // coefficients of the first non square of the field for the square roots, None in characteristic 2
+    non_square: Option<Vec<FiniteFieldElement>>,
+}
+
+impl ExtensionField {
+    // F_p[a] / (f) with the coefficients of f from the constant term up, f is divided by its leading
+    // coefficient. Fails when f has degree below 2 or is not irreducible
+    pub fn new<C: Into<BigInt>>(base: &PrimeField, coefficients: Vec<C>) -> Result<ExtensionField, String> {
+        let mut coefficients: Vec<FiniteFieldElement> = coefficients.into_iter().map(|c| base.element(c)).collect();
+        if coefficients.is_empty() {
+            coefficients.push(base.element(0));
+        }
+        let modulus = Polynomial::new(coefficients).monic();
+        let degree = modulus.degree().unwrap_or(0);
+        if degree < 2 {
+            return Err(format!("The modulus of an extension needs degree at least 2, got {}", degree));
+        }
+        if !is_irreducible(&modulus, &base.modulus) {
+            return Err(format!("Modulus {} is not irreducible over {}", PolynomialDisplay(modulus.coefficients()), base));
+        }
+        let mut field = ExtensionField {
+            base: base.clone(),
+            modulus,
+            non_square: None,
+        };
+        if base.modulus != BigUint::from(2u32) {
+            // euler's criterion, z is not a square iff z^((q-1)/2) = -1
+            let half = &(&field.size() - &BigUint::one()) >> 1;
+            let zero = ExtensionFieldElement::new(vec![0], &Arc::new(field.clone()));
+            let minus_one = -zero.one();
+            let non_square = std::iter::successors(Some(zero), |z| z.successor())
+                .find(|z| z.pow_uint(&half) == minus_one)
+                .expect("Error in ExtensionField::new there is no non square");
+            field.non_square = Some(non_square.coefficients);
+        }
+        Ok(field)
+    }
+
+    // k, the degree of the extension
+    pub fn degree(&self) -> usize {
+        self.modulus.degree().expect("Error in ExtensionField::degree zero modulus")
+    }
+
+    pub fn modulus(&self) -> &Polynomial<FiniteFieldElement> {
+        &self.modulus
+    }
+}
+
+// rabin's test: f of degree k is irreducible over F_p iff a^(p^k) = a mod f and gcd(a^(p^(k/r)) - a, f) = 1
+// for every prime r dividing k
+fn is_irreducible(f: &Polynomial<FiniteFieldElement>, p: &BigUint) -> bool {
+    let k = f.degree().expect("Error in is_irreducible zero polynomial");
+    let a = Polynomial::x(&f.coefficient(0));
+    // a^(p^i) mod f for i = 0..=k, each one the p-th power of the previous
+    let mut powers = vec![a.rem(f).expect("Error in is_irreducible rem")];
+    for i in 1..=k {
+        powers.push(powers[i - 1].pow_mod(p, f).expect("Error in is_irreducible pow_mod"));
+    }
+    if powers[k] != powers[0] {
+        return false;
+    }
+    (2..=k)
+        .filter(|r| k.is_multiple_of(*r) && (2..*r).all(|d| r % d != 0))
+        .all(|r| (&powers[k / r] - &a).gcd(f).degree() == Some(0))
+}
+
+impl FiniteField for ExtensionField {
+    fn characteristic(&self) -> BigUint {
+        self.base.modulus.clone()
+    }
+
+    // p^k
+    fn size(&self) -> BigUint {
+        (0..self.degree()).fold(BigUint::one(), |size, _| &size * &self.base.modulus)
+    }
+}
+
+impl Display for ExtensionField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[a]/({})", self.base, PolynomialDisplay(self.modulus.coefficients()))
+    }
+}
+
+// element of an extension field, the k coefficients of its polynomial in a from the constant term up
+#[derive(Debug, Clone)]
+pub struct ExtensionFieldElement {
+    pub coefficients: Vec<FiniteFieldElement>,
+    pub field: Arc<ExtensionField>,
+}
+
+impl ExtensionFieldElement {
+    // the polynomial with these coefficients (from the constant term up) reduced modulo f
+    pub fn new<C: Into<BigInt>>(coefficients: Vec<C>, field: &Arc<ExtensionField>) -> ExtensionFieldElement {
+        let mut coefficients: Vec<FiniteFieldElement> = coefficients.into_iter().map(|c| field.base.element(c)).collect();
+        coefficients.push(field.base.element(0));
+        let polynomial = Polynomial::new(coefficients).rem(&field.modulus).expect("Error in ExtensionFieldElement::new rem");
+        ExtensionFieldElement::from_polynomial(&polynomial, field)
+    }
+
+    // the generator a of the extension
+    pub fn generator(field: &Arc<ExtensionField>) -> ExtensionFieldElement {
+        ExtensionFieldElement::new(vec![0, 1], field)
+    }
+
+    fn from_polynomial(polynomial: &Polynomial<FiniteFieldElement>, field: &Arc<ExtensionField>) -> ExtensionFieldElement {
+        ExtensionFieldElement {
+            coefficients: (0..field.degree()).map(|i| polynomial.coefficient(i)).collect(),
+            field: field.clone(),
+        }
+    }
+
+    pub fn to_polynomial(&self) -> Polynomial<FiniteFieldElement> {
+        Polynomial::new(self.coefficients.clone())
+    }
+
+    // elements of the same field usually share the Arc, so the pointers are compared first
+    fn same_field(&self, b: &ExtensionFieldElement) -> bool {
+        Arc::ptr_eq(&self.field, &b.field) || self.field == b.field
+    }
+
+    fn check_field(&self, b: &ExtensionFieldElement) -> Result<(), String> {
+        if !self.same_field(b) {
+            Err("Field mismatch".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    // addition and subtraction coefficient by coefficient
+    pub fn add(&self, b: &ExtensionFieldElement) -> Result<ExtensionFieldElement, String> {
+        self.check_field(b)?;
+        Ok(ExtensionFieldElement {
+            coefficients: self.coefficients.iter().zip(&b.coefficients).map(|(x, y)| x + y).collect(),
+            field: self.field.clone(),
+        })
+    }
+
+    pub fn sub(&self, b: &ExtensionFieldElement) -> Result<ExtensionFieldElement, String> {
+        self.check_field(b)?;
+        Ok(ExtensionFieldElement {
+            coefficients: self.coefficients.iter().zip(&b.coefficients).map(|(x, y)| x - y).collect(),
+            field: self.field.clone(),
+        })
+    }
+
+    // product of the polynomials reduced modulo f
+    pub fn mul(&self, b: &ExtensionFieldElement) -> Result<ExtensionFieldElement, String> {
+        self.check_field(b)?;
+        let product = self.to_polynomial().mul_mod(&b.to_polynomial(), &self.field.modulus)?;
+        Ok(ExtensionFieldElement::from_polynomial(&product, &self.field))
+    }
+
+    // the inverse of b is the s of the extended euclidean algorithm with s b = 1 mod f
+    pub fn div(&self, b: &ExtensionFieldElement) -> Result<ExtensionFieldElement, String> {
+        self.check_field(b)?;
+        if b.is_zero() {
+            return Err("Zero division".to_string());
+        }
+        let inverse = b.to_polynomial().inverse_mod(&self.field.modulus)?;
+        self.mul(&ExtensionFieldElement::from_polynomial(&inverse, &self.field))
+    }
+}
+
+impl_field_operators!(ExtensionFieldElement);
+
+impl std::ops::Neg for &ExtensionFieldElement {
+    type Output = ExtensionFieldElement;
+    fn neg(self) -> ExtensionFieldElement {
+        ExtensionFieldElement {
+            coefficients: self.coefficients.iter().map(|c| -c).collect(),
+            field: self.field.clone(),
+        }
+    }
+}
+
+impl std::ops::Neg for ExtensionFieldElement {
+    type Output = ExtensionFieldElement;
+    fn neg(self) -> ExtensionFieldElement {
+        -&self
+    }
+}
+
+impl Field for ExtensionFieldElement {
+    type Parent = ExtensionField;
+
+    fn field(&self) -> ExtensionField {
+        (*self.field).clone()
+    }
+
+    fn zero(&self) -> ExtensionFieldElement {
+        self.integer(0)
+    }
+
+    fn one(&self) -> ExtensionFieldElement {
+        self.integer(1)
+    }
+
+    // an integer is a constant polynomial, it needs no reduction
+    fn integer(&self, n: i64) -> ExtensionFieldElement {
+        let mut coefficients = vec![self.field.base.element(0); self.field.degree()];
+        coefficients[0] = self.field.base.element(n);
+        ExtensionFieldElement {
+            coefficients,
+            field: self.field.clone(),
+        }
+    }
+
+    fn inv(&self) -> Result<ExtensionFieldElement, String> {
+        self.one().div(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coefficients.iter().all(|c| c.is_zero())
+    }
+
+    // the coefficients are the digits of a number in base p, from the constant term up
+    fn successor(&self) -> Option<ExtensionFieldElement> {
+        let mut coefficients = self.coefficients.clone();
+        for c in coefficients.iter_mut() {
+            match c.successor() {
+                Some(next) => {
+                    *c = next;
+                    return Some(ExtensionFieldElement {
+                        coefficients,
+                        field: self.field.clone(),
+                    });
+                }
+                None => *c = c.zero(),
+            }
+        }
+        None
+    }
+
+    fn conditional_swap(&mut self, other: &mut ExtensionFieldElement, choice: bool) {
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients.iter_mut()) {
+            a.conditional_swap(b, choice);
+        }
+    }
+
+    // tonelli-shanks with the non square found when the field was built
+    fn square_root(&self) -> Option<ExtensionFieldElement> {
+        match &self.field.non_square {
+            Some(coefficients) => {
+                let non_square = ExtensionFieldElement {
+                    coefficients: coefficients.clone(),
+                    field: self.field.clone(),
+                };
+                tonelli_shanks(self, &non_square)
+            }
+            None => Some(self.pow_uint(&(&self.field.size() >> 1))),
+        }
+    }
+}
+
+impl PartialEq for ExtensionFieldElement {
+    fn eq(&self, other: &ExtensionFieldElement) -> bool {
+        self.coefficients == other.coefficients && self.same_field(other)
+    }
+}
+
+impl Display for ExtensionFieldElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value = PolynomialDisplay(&self.coefficients);
+        if f.alternate() {
+            // in parentheses when there is more than one term, as a coefficient of a curve
+            if self.coefficients.iter().filter(|c| !c.is_zero()).count() > 1 {
+                return write!(f, "({})", value);
+            }
+            return write!(f, "{}", value);
+        }
+        write!(f, "(value: {},field: {})", value, self.field)
+    }
+}
+
+// polynomial in a from its coefficients, with the highest degree first: a^2 + 3a + 1
+struct PolynomialDisplay<'a>(&'a [FiniteFieldElement]);
+
+impl Display for PolynomialDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut terms = Vec::new();
+        for (i, c) in self.0.iter().enumerate().rev().filter(|(_, c)| !c.is_zero()) {
+            let coefficient = if c.value.is_one() && i > 0 { String::new() } else { c.value.to_string() };
+            terms.push(match i {
+                0 => coefficient,
+                1 => format!("{}a", coefficient),
+                _ => format!("{}a^{}", coefficient, i),
+            });
+        }
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+// tests for the extension fields
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // F_(43^2) = F_43[a] / (a^2 + 1), -1 is not a square modulo 43 = 3 mod 4
+    fn f43_2() -> Arc<ExtensionField> {
+        Arc::new(ExtensionField::new(&PrimeField::new(43).unwrap(), vec![1, 0, 1]).unwrap())
+    }
+
+    // F_16 = F_2[a] / (a^4 + a + 1)
+    fn f16() -> Arc<ExtensionField> {
+        Arc::new(ExtensionField::new(&PrimeField::new(2).unwrap(), vec![1, 1, 0, 0, 1]).unwrap())
+    }
+
+    fn elements(field: &Arc<ExtensionField>) -> Vec<ExtensionFieldElement> {
+        std::iter::successors(Some(ExtensionFieldElement::new(vec![0], field)), |x| x.successor()).collect()
+    }
+
+    #[test]
+    fn test_new() {
+        let field = f43_2();
+        assert_eq!(field.degree(), 2);
+        assert_eq!(field.size(), BigUint::from(1849u32));
+        assert_eq!(field.characteristic(), BigUint::from(43u32));
+        assert_eq!(field.to_string(), "F_43[a]/(a^2 + 1)");
+        // the modulus is made monic
+        let monic = ExtensionField::new(&PrimeField::new(43).unwrap(), vec![2, 0, 2]).unwrap();
+        assert_eq!(monic, *field);
+        assert_eq!(f16().to_string(), "F_2[a]/(a^4 + a + 1)");
+
+        // a^2 + 1 = (a - 2)(a + 2) over F_5 and a^4 + a^2 + 1 = (a^2 + a + 1)^2 over F_2
+        let f5 = PrimeField::new(5).unwrap();
+        assert_eq!(ExtensionField::new(&f5, vec![1, 0, 1]).unwrap_err(), "Modulus a^2 + 1 is not irreducible over F_5");
+        assert!(ExtensionField::new(&PrimeField::new(2).unwrap(), vec![1, 0, 1, 0, 1]).is_err());
+        // a^4 + 1 has no roots over F_3 but it is (a^2 + a + 2)(a^2 + 2a + 2)
+        assert!(ExtensionField::new(&PrimeField::new(3).unwrap(), vec![1, 0, 0, 0, 1]).is_err());
+        assert!(ExtensionField::new(&f5, vec![1, 1]).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let field = f43_2();
+        let a = ExtensionFieldElement::generator(&field);
+        let x = ExtensionFieldElement::new(vec![5, 3], &field);
+        assert_eq!(a.square(), a.integer(-1));
+        assert_eq!(&x * &a, ExtensionFieldElement::new(vec![-3, 5], &field));
+        assert_eq!(&x + &x.integer(40), ExtensionFieldElement::new(vec![2, 3], &field));
+        assert_eq!(ExtensionFieldElement::new(vec![1, 0, 1], &field), x.zero());
+        // frobenius x^p is the conjugate 5 - 3a
+        assert_eq!(x.pow_uint(&BigUint::from(43u32)), ExtensionFieldElement::new(vec![5, -3], &field));
+
+        let elements = elements(&field);
+        assert_eq!(elements.len(), 1849);
+        let q_minus_one = BigUint::from(1848u32);
+        for y in elements.iter().skip(1) {
+            assert_eq!(y.inv().unwrap() * y, y.one());
+            assert_eq!(y.pow_uint(&q_minus_one), y.one());
+        }
+        assert_eq!(x.div(&x.zero()).unwrap_err(), "Zero division");
+        assert_eq!(x.to_string(), "(value: 3a + 5,field: F_43[a]/(a^2 + 1))");
+        assert_eq!(format!("{:#}", x), "(3a + 5)");
+        assert_eq!(format!("{:#}", a), "a");
+    }
+
+    #[test]
+    fn test_binary_field() {
+        let field = f16();
+        let a = ExtensionFieldElement::generator(&field);
+        // characteristic 2, x + x = 0 and a^4 = a + 1
+        assert!((a.clone() + &a).is_zero());
+        assert_eq!(a.pow_uint(&BigUint::from(4u32)), a.clone() + a.one());
+        // a generates the 15 non zero elements
+        assert!(a.pow_uint(&BigUint::from(5u32)) != a.one() && a.pow_uint(&BigUint::from(3u32)) != a.one());
+        assert_eq!(a.pow_uint(&BigUint::from(15u32)), a.one());
+        let elements = elements(&field);
+        assert_eq!(elements.len(), 16);
+        for x in elements.iter().skip(1) {
+            assert_eq!(x.inv().unwrap() * x, x.one());
+        }
+    }
+
+    #[test]
+    fn test_square_root() {
+        for field in [f43_2(), f16()] {
+            let mut squares = 0;
+            for x in elements(&field) {
+                if let Some(root) = x.square_root() {
+                    assert_eq!(root.square(), x);
+                    squares += 1;
+                }
+            }
+            // every element in characteristic 2, half of the non zero ones plus zero otherwise
+            let q = field.size().to_u64().unwrap();
+            assert_eq!(squares, if q % 2 == 0 { q } else { q.div_ceil(2) });
+        }
+        // the elements of F_43 are squares in F_(43^2)
+        let field = f43_2();
+        assert!((0..43).all(|n| ExtensionFieldElement::new(vec![n], &field).square_root().is_some()));
+    }
+
+    #[test]
+    fn test_field_mismatch() {
+        let x = ExtensionFieldElement::generator(&f43_2());
+        let other = Arc::new(ExtensionField::new(&PrimeField::new(43).unwrap(), vec![3, 1, 1]).unwrap());
+        let y = ExtensionFieldElement::generator(&other);
+        assert_eq!(x.add(&y).unwrap_err(), "Field mismatch");
+        assert_ne!(x.field(), y.field());
+        assert_eq!(x.field(), *f43_2());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::big_int::BigUint;
+use crate::primality::is_prime;
+
+// primes for trial division before pollard's rho
+const TRIAL_DIVISION_BOUND: u64 = 1000;
+
+// prime factorization as (prime, exponent) pairs sorted by prime, the factorization of 1 is empty.
+// small factors are removed with trial division and the rest is split with pollard's rho, which
+// finds a factor q in about sqrt(q) steps, practical for numbers up to about 100 bits
+pub fn factorize(n: &BigUint) -> Result<Vec<(BigUint, u32)>, String> {
+    if n.is_zero() {
+        return Err("Can't factorize 0".to_string());
+    }
+    let mut factors: Vec<(BigUint, u32)> = Vec::new();
+    let mut n = n.clone();
+    for d in 2..TRIAL_DIVISION_BOUND {
+        let d = BigUint::from(d);
+        let mut exponent = 0;
+        while (&n % &d).is_zero() {
+            n = &n / &d;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((d, exponent));
+        }
+    }
+    let mut pending = vec![n];
+    while let Some(m) = pending.pop() {
+        if m.is_one() {
+            continue;
+        }
+        if is_prime(&m) {
+            match factors.iter_mut().find(|(q, _)| *q == m) {
+                Some((_, exponent)) => *exponent += 1,
+                None => factors.push((m, 1)),
+            }
+            continue;
+        }
+        let d = pollard_rho(&m);
+        pending.push(&m / &d);
+        pending.push(d);
+    }
+    factors.sort();
+    Ok(factors)
+}
+
+// non trivial factor of an odd composite n
+// the sequence x <- x^2 + c mod n repeats modulo an unknown factor q long before it does modulo n, the
+// cycle is found with floyd's tortoise and hare by gcd(|x - y|, n). If the gcd is n the cycles coincide
+// and another c is tried
+fn pollard_rho(n: &BigUint) -> BigUint {
+    let mut c = BigUint::one();
+    loop {
+        let step = |x: &BigUint| &(&(x * x) + &c) % n;
+        let mut x = BigUint::from(2u32);
+        let mut y = x.clone();
+        loop {
+            x = step(&x);
+            y = step(&step(&y));
+            let difference = if x > y { &x - &y } else { &y - &x };
+            let d = difference.gcd(n);
+            if d == *n {
+                break;
+            }
+            if !d.is_one() {
+                return d;
+            }
+        }
+        c = &c + &BigUint::one();
+    }
+}
+
+// tests for the factorization
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(factors: &[(BigUint, u32)]) -> BigUint {
+        factors.iter().fold(BigUint::one(), |acc, (q, e)| (0..*e).fold(acc, |acc, _| &acc * q))
+    }
+
+    #[test]
+    fn test_small_numbers() {
+        assert!(factorize(&BigUint::one()).unwrap().is_empty());
+        assert!(factorize(&BigUint::zero()).is_err());
+        assert_eq!(factorize(&BigUint::from(1039u32)).unwrap(), vec![(BigUint::from(1039u32), 1)]);
+        let factors = factorize(&BigUint::from(360u32)).unwrap();
+        assert_eq!(factors, vec![(BigUint::from(2u32), 3), (BigUint::from(3u32), 2), (BigUint::from(5u32), 1)]);
+        for n in 1..3000u32 {
+            let factors = factorize(&BigUint::from(n)).unwrap();
+            assert_eq!(product(&factors), BigUint::from(n));
+            assert!(factors.iter().all(|(q, _)| is_prime(q)));
+        }
+    }
+
+    #[test]
+    fn test_pollard_rho() {
+        // products of primes bigger than the trial division bound
+        let p = BigUint::from(1000003u32);
+        let q = BigUint::from(2147483647u32);
+        let n = &(&(&p * &p) * &q) * &BigUint::from(12u32);
+        assert_eq!(
+            factorize(&n).unwrap(),
+            vec![(BigUint::from(2u32), 2), (BigUint::from(3u32), 1), (p.clone(), 2), (q.clone(), 1)]
+        );
+        // 2^64 + 1 = 274177 * 67280421310721
+        let n = &(BigUint::one() << 64) + &BigUint::one();
+        assert_eq!(
+            factorize(&n).unwrap(),
+            vec![(BigUint::from(274177u32), 1), (BigUint::from(67280421310721u64), 1)]
+        );
+        let n = &q * &BigUint::from(4294967291u32);
+        assert_eq!(factorize(&n).unwrap().len(), 2);
+    }
+}
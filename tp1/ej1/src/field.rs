@@ -0,0 +1,193 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::big_int::BigUint;
+
+// a finite field F_q, q = p^k, as a value: the modulus of a prime field, the base field and modulus
+// polynomial of an extension. Two elements belong to the same field iff their fields are equal
+pub trait FiniteField: Clone + PartialEq + Debug + Display {
+    fn characteristic(&self) -> BigUint;
+
+    // number of elements q
+    fn size(&self) -> BigUint;
+}
+
+// operations the curve arithmetic needs from the base field
+// an element knows which field it belongs to (field), so the constants are built from an existing
+// element with zero / one / integer.
+// addition, subtraction, multiplication and negation are the std::ops operators, which panic
+// when the operands belong to different fields.
+// Display with the alternate flag ({:#}) writes the element without its field, as the curves print
+// their coefficients
+pub trait Field:
+    Sized + Clone + PartialEq + Debug + Display
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+    + for<'a> Add<&'a Self, Output = Self> + for<'a> Sub<&'a Self, Output = Self> + for<'a> Mul<&'a Self, Output = Self>
+{
+    type Parent: FiniteField;
+
+    // the field self belongs to
+    fn field(&self) -> Self::Parent;
+
+    // additive identity of the field self belongs to
+    fn zero(&self) -> Self;
+
+    // multiplicative identity of the field self belongs to
+    fn one(&self) -> Self;
+
+    // the integer n as an element of the field of self (n times one)
+    fn integer(&self, n: i64) -> Self;
+
+    // multiplicative inverse, fails for zero
+    fn inv(&self) -> Result<Self, String>;
+
+    fn is_zero(&self) -> bool;
+
+    // the element after self in an enumeration of the field that starts at zero, None for the last one.
+    // walking from zero goes through every element (see EllipticCurve::points)
+    fn successor(&self) -> Option<Self>;
+
+    // swaps self and other when choice is true, with the same field level operations for both values of
+    // choice (see Point::scalar_mul_ladder). Values of big prime fields keep the width of the modulus, but
+    // it is not constant time: the arithmetic of BigUint depends on the values
+    fn conditional_swap(&mut self, other: &mut Self, choice: bool);
+
+    fn square(&self) -> Self {
+        self.clone() * self
+    }
+
+    fn div(&self, other: &Self) -> Result<Self, String> {
+        Ok(self.clone() * &other.inv()?)
+    }
+
+    // self^e with square and multiply
+    fn pow_uint(&self, exponent: &BigUint) -> Self {
+        let mut result = self.one();
+        for i in (0..exponent.bits()).rev() {
+            result = result.square();
+            if exponent.bit(i) {
+                result = result * self;
+            }
+        }
+        result
+    }
+
+    // a square root of self, None when self is not a square.
+    // in characteristic 2 squaring is a bijection and the root is self^(q/2), otherwise tonelli-shanks
+    // with the first non square of the enumeration of the field (a^((q-1)/2) = -1 by euler's criterion)
+    fn square_root(&self) -> Option<Self> {
+        let field = self.field();
+        let q = field.size();
+        if field.characteristic() == BigUint::from(2u32) {
+            return Some(self.pow_uint(&(&q >> 1)));
+        }
+        let half = &(&q - &BigUint::one()) >> 1;
+        let minus_one = -self.one();
+        let non_square = std::iter::successors(Some(self.zero()), |z| z.successor())
+            .find(|z| z.pow_uint(&half) == minus_one)
+            .expect("Error in Field::square_root there is no non square");
+        tonelli_shanks(self, &non_square)
+    }
+}
+
+// square root of a in a field of odd size q with a known non square z, None when a is not a square
+// algorithm:
+// 1. write q - 1 = t 2^s with t odd
+// 2. m <- s, c <- z^t, u <- a^t, r <- a^((t+1)/2)
+// 3. while u != 1
+// 4.   find the least i (0 < i < m) with u^(2^i) = 1, if there is none a is not a square
+// 5.   b <- c^(2^(m-i-1)), m <- i, c <- b^2, u <- u b^2, r <- r b
+// 6. return r
+pub(crate) fn tonelli_shanks<F: Field>(a: &F, non_square: &F) -> Option<F> {
+    if a.is_zero() {
+        return Some(a.clone());
+    }
+    let q_minus_one = &a.field().size() - &BigUint::one();
+    let mut s = 0;
+    while !q_minus_one.bit(s) {
+        s += 1;
+    }
+    let t = &q_minus_one >> s;
+    let one = a.one();
+
+    let mut m = s;
+    let mut c = non_square.pow_uint(&t);
+    let mut u = a.pow_uint(&t);
+    let mut r = a.pow_uint(&(&(&t + &BigUint::one()) >> 1));
+    while u != one {
+        let mut i = 0;
+        let mut u_pow = u.clone();
+        while u_pow != one {
+            u_pow = u_pow.square();
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+        let mut b = c;
+        for _ in 0..m - i - 1 {
+            b = b.square();
+        }
+        m = i;
+        c = b.square();
+        u = u * &c;
+        r = r * &b;
+    }
+    Some(r)
+}
+
+// operator overloads for a field element type with fallible `add`, `sub`, `mul` and `div` methods,
+// so formulas can be written as `s * s - x1 - x2`.
+// operators can't return an error: a modulus mismatch or a division by zero panics with the same
+// message the fallible methods return
+macro_rules! impl_field_operators {
+    ($t:ty) => {
+        impl_field_operators!($t, Add, add, AddAssign, add_assign);
+        impl_field_operators!($t, Sub, sub, SubAssign, sub_assign);
+        impl_field_operators!($t, Mul, mul, MulAssign, mul_assign);
+        impl_field_operators!($t, Div, div, DivAssign, div_assign);
+    };
+    ($t:ty, $tr:ident, $method:ident, $assign_tr:ident, $assign_method:ident) => {
+        impl std::ops::$tr<&$t> for &$t {
+            type Output = $t;
+            fn $method(self, rhs: &$t) -> $t {
+                <$t>::$method(self, rhs).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+
+        impl std::ops::$tr<$t> for &$t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                std::ops::$tr::$method(self, &rhs)
+            }
+        }
+
+        impl std::ops::$tr<&$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: &$t) -> $t {
+                std::ops::$tr::$method(&self, rhs)
+            }
+        }
+
+        impl std::ops::$tr<$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                std::ops::$tr::$method(&self, &rhs)
+            }
+        }
+
+        impl std::ops::$assign_tr<&$t> for $t {
+            fn $assign_method(&mut self, rhs: &$t) {
+                *self = std::ops::$tr::$method(&*self, rhs);
+            }
+        }
+
+        impl std::ops::$assign_tr<$t> for $t {
+            fn $assign_method(&mut self, rhs: $t) {
+                *self = std::ops::$tr::$method(&*self, &rhs);
+            }
+        }
+    };
+}
+
+pub(crate) use impl_field_operators;
@@ -1,21 +1,85 @@
 use std::fmt::{Display, Formatter, self};
+use crate::big_int::{BigInt, BigUint};
+use crate::field::{Field, FiniteField, impl_field_operators};
+use crate::primality::is_prime;
+use rand::Rng;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct FiniteFieldElement {
-    pub value: i32,
-    pub modulus: i32,
+    pub value: BigUint,
+    pub modulus: BigUint,
+}
+
+// prime field F_p with a validated modulus, used to build elements when the modulus must be a prime
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrimeField {
+    pub modulus: BigUint,
+}
+
+impl PrimeField {
+    pub fn new<M: Into<BigInt>>(modulus: M) -> Result<PrimeField, String> {
+        let modulus = modulus.into();
+        if modulus <= BigInt::one() {
+            return Err(format!("Modulus must be greater than 1, got {}", modulus));
+        }
+        let modulus = modulus.into_magnitude();
+        if !is_prime(&modulus) {
+            return Err(format!("Modulus {} is not prime", modulus));
+        }
+        Ok(PrimeField { modulus })
+    }
+
+    pub fn element<V: Into<BigInt>>(&self, value: V) -> FiniteFieldElement {
+        FiniteFieldElement::new(value, self.modulus.clone())
+    }
+
+    // random element, one limb more than the modulus makes the bias of the reduction negligible
+    pub fn random_element<R: Rng>(&self, rng: &mut R) -> FiniteFieldElement {
+        let limbs = (0..=self.modulus.limbs().len()).map(|_| rng.gen::<u64>()).collect();
+        self.element(BigUint::from_limbs(limbs))
+    }
+}
+
+impl FiniteField for PrimeField {
+    fn characteristic(&self) -> BigUint {
+        self.modulus.clone()
+    }
+
+    fn size(&self) -> BigUint {
+        self.modulus.clone()
+    }
+}
+
+impl Display for PrimeField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "F_{}", self.modulus)
+    }
 }
 
 impl FiniteFieldElement {
-    pub fn new(value: i32, modulus: i32) -> FiniteFieldElement {
+    // the modulus is not validated (composite moduli are allowed for plain modular arithmetic)
+    // and a zero modulus panics, use new_checked or PrimeField to build elements of a field
+    pub fn new<V: Into<BigInt>, M: Into<BigInt>>(value: V, modulus: M) -> FiniteFieldElement {
+        let modulus = BigUint::try_from(modulus.into()).expect("Error in FiniteFieldElement::new negative modulus");
+        FiniteFieldElement::from_reduced(value.into().rem_euclid(&modulus), modulus)
+    }
+
+    // element from a value already reduced mod the modulus. Values are kept padded to the number of
+    // limbs of the modulus so every element of a field has the same width (see conditional_swap)
+    pub(crate) fn from_reduced(value: BigUint, modulus: BigUint) -> FiniteFieldElement {
         FiniteFieldElement {
-            value: value % modulus,
-            modulus: modulus,
+            value: value.with_width(modulus.limbs().len()),
+            modulus,
         }
     }
 
+    // element of F_p, fails when the modulus is not a prime
+    pub fn new_checked<V: Into<BigInt>, M: Into<BigInt>>(value: V, modulus: M) -> Result<FiniteFieldElement, String> {
+        Ok(PrimeField::new(modulus)?.element(value))
+    }
+
     fn check_zero(&self, b: &FiniteFieldElement) -> Result<(), String> {
-        if b.value == 0 {
+        if b.value.is_zero() {
             Err("Zero division".to_string())
         } else {
             Ok(())
@@ -32,55 +96,306 @@ impl FiniteFieldElement {
 
     pub fn add(&self, b: &FiniteFieldElement) -> Result<FiniteFieldElement, String> {
         self.check_modulus(b)?; // check if modulus is the same
-        Ok(FiniteFieldElement {
-            value: (self.value + b.value).rem_euclid(self.modulus),
-            modulus: self.modulus,
-        })
+        let mut value = &self.value + &b.value;
+        if value >= self.modulus {
+            value = &value - &self.modulus;
+        }
+        Ok(FiniteFieldElement::from_reduced(value, self.modulus.clone()))
     }
 
     pub fn sub(&self, b: &FiniteFieldElement) -> Result<FiniteFieldElement, String> {
         self.check_modulus(b)?;
-        Ok(FiniteFieldElement {
-            value: (self.value - b.value).rem_euclid(self.modulus),
-            modulus: self.modulus,
-        })
+        let value = if self.value >= b.value {
+            &self.value - &b.value
+        } else {
+            &(&self.value + &self.modulus) - &b.value
+        };
+        Ok(FiniteFieldElement::from_reduced(value, self.modulus.clone()))
     }
 
     pub fn mul(&self, b: &FiniteFieldElement) -> Result<FiniteFieldElement, String> {
         self.check_modulus(b)?;
-        Ok(FiniteFieldElement {
-            value: (self.value * b.value).rem_euclid(self.modulus),
-            modulus: self.modulus,
-        })
+        Ok(FiniteFieldElement::from_reduced(&(&self.value * &b.value) % &self.modulus, self.modulus.clone()))
     }
 
     pub fn div(&self, b: &FiniteFieldElement) -> Result<FiniteFieldElement, String> {
         self.check_modulus(b)?;
         self.check_zero(b)?;
-        let (gcd, x, _) = ExtendedEuclideanAlgorithm::extended_gcd(b.value, self.modulus);
-        if gcd != 1 {
+        let (gcd, x, _) = ExtendedEuclideanAlgorithm::extended_gcd(&BigInt::from(&b.value), &BigInt::from(&self.modulus));
+        if !gcd.is_one() {
             return Err(format!("{} and {} are not coprimes", self.value, self.modulus));
         }
-        Ok(FiniteFieldElement {
-            value: (self.value * x).rem_euclid(self.modulus),
-            modulus: self.modulus,
-        })
+        Ok(FiniteFieldElement::from_reduced((&BigInt::from(&self.value) * &x).rem_euclid(&self.modulus), self.modulus.clone()))
+    }
+
+    // square and multiply exponentiation, reducing after every product so intermediate values stay below modulus^2
+    // a negative exponent raises the inverse of the element: a^-b = (a^-1)^b
+    // algorithm (left to right):
+    // 1. result <- 1
+    // 2. for each bit e_i of the exponent from the most significant one
+    // 3.   result <- result^2
+    // 4.   if e_i = 1 then result <- result * base
+    // 5. return result
+    pub fn pow<E: Into<BigInt>>(&self, b: E) -> Result<FiniteFieldElement, String> {
+        let exponent = b.into();
+        let one = FiniteFieldElement::new(1, self.modulus.clone());
+        let base = if exponent.is_negative() {
+            one.div(self)?
+        } else {
+            self.clone()
+        };
+        let exponent = exponent.magnitude();
+        let mut result = one;
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul(&result)?;
+            if exponent.bit(i) {
+                result = result.mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    // legendre symbol (self / p) of the element, the modulus must be an odd prime
+    pub fn legendre(&self) -> Result<i8, String> {
+        legendre(&BigInt::from(&self.value), &self.modulus)
+    }
+
+    // zero counts as a square
+    fn is_square(&self) -> bool {
+        self.modulus == BigUint::from(2u32) || self.legendre().expect("Error in is_square legendre") != -1
     }
 
-    pub fn pow(&self, b: u32) -> Result<FiniteFieldElement, String> {
-        Ok(FiniteFieldElement {
-            value: self.value.pow(b).rem_euclid(self.modulus),
-            modulus: self.modulus,
-        })
+    fn exponent_pow(&self, exponent: BigUint) -> FiniteFieldElement {
+        self.pow(BigInt::from(exponent)).expect("Error in sqrt pow")
+    }
+
+    // both square roots (r, -r) with the smaller one first, or None when self is not a square
+    // the modulus must be prime. Uses the direct formulas when p = 3 mod 4 or p = 5 mod 8
+    // and Tonelli-Shanks otherwise
+    pub fn sqrt(&self) -> Option<(FiniteFieldElement, FiniteFieldElement)> {
+        let p = &self.modulus;
+        if p.bits() <= 2 || self.value.is_zero() || p.limbs()[0] % 4 == 1 && p.limbs()[0] % 8 != 5 {
+            return self.sqrt_tonelli_shanks();
+        }
+        if !self.is_square() {
+            return None;
+        }
+        let root = if p.limbs()[0] % 4 == 3 {
+            // r = a^((p+1)/4), since r^2 = a^((p-1)/2) a = a
+            self.exponent_pow(&(p + &BigUint::one()) >> 2)
+        } else {
+            // p = 5 mod 8 (Atkin): v = (2a)^((p-5)/8), i = 2a v^2, r = a v (i - 1)
+            let two_a = self + self;
+            let v = two_a.exponent_pow(&(p - &BigUint::from(5u32)) >> 3);
+            let i = &two_a * &v * &v;
+            self * &v * (i - self.one())
+        };
+        Some(FiniteFieldElement::root_pair(root))
+    }
+
+    // tonelli-shanks for any odd prime modulus
+    // algorithm:
+    // 1. write p - 1 = q 2^s with q odd and find a non residue z
+    // 2. m <- s, c <- z^q, t <- a^q, r <- a^((q+1)/2)
+    // 3. while t != 1
+    // 4.   find the least i (0 < i < m) with t^(2^i) = 1
+    // 5.   b <- c^(2^(m-i-1)), m <- i, c <- b^2, t <- t b^2, r <- r b
+    // 6. return r
+    pub fn sqrt_tonelli_shanks(&self) -> Option<(FiniteFieldElement, FiniteFieldElement)> {
+        if self.value.is_zero() || self.modulus == BigUint::from(2u32) {
+            return Some((self.clone(), self.clone()));
+        }
+        if !self.is_square() {
+            return None;
+        }
+        let p_minus_one = &self.modulus - &BigUint::one();
+        let mut s = 0;
+        while !p_minus_one.bit(s) {
+            s += 1;
+        }
+        let q = &p_minus_one >> s;
+        let mut z = self.integer(2);
+        while z.is_square() {
+            z += self.one();
+        }
+
+        let mut m = s;
+        let mut c = z.exponent_pow(q.clone());
+        let mut t = self.exponent_pow(q.clone());
+        let mut r = self.exponent_pow(&(&q + &BigUint::one()) >> 1);
+        while !t.value.is_one() {
+            let mut i = 0;
+            let mut t_pow = t.clone();
+            while !t_pow.value.is_one() {
+                t_pow = t_pow.square();
+                i += 1;
+            }
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = b.square();
+            }
+            m = i;
+            c = b.square();
+            t *= &c;
+            r *= &b;
+        }
+        Some(FiniteFieldElement::root_pair(r))
+    }
+
+    // cipolla's algorithm for any odd prime modulus
+    // find t such that w = t^2 - a is not a square, then in F_p(sqrt(w)) the root is
+    // r = (t + sqrt(w))^((p+1)/2), which lies in F_p
+    pub fn sqrt_cipolla(&self) -> Option<(FiniteFieldElement, FiniteFieldElement)> {
+        if self.value.is_zero() || self.modulus == BigUint::from(2u32) {
+            return Some((self.clone(), self.clone()));
+        }
+        if !self.is_square() {
+            return None;
+        }
+        let mut t = self.one();
+        while (t.square() - self).is_square() {
+            t += self.one();
+        }
+        let w = t.square() - self;
+        // elements x + y sqrt(w) are pairs (x, y)
+        let mul = |(x1, y1): &(FiniteFieldElement, FiniteFieldElement), (x2, y2): &(FiniteFieldElement, FiniteFieldElement)| {
+            (x1 * x2 + y1 * y2 * &w, x1 * y2 + x2 * y1)
+        };
+        let exponent = &(&self.modulus + &BigUint::one()) >> 1;
+        let base = (t, self.one());
+        let mut result = (self.one(), self.zero());
+        for i in (0..exponent.bits()).rev() {
+            result = mul(&result, &result);
+            if exponent.bit(i) {
+                result = mul(&result, &base);
+            }
+        }
+        Some(FiniteFieldElement::root_pair(result.0))
+    }
+
+    fn root_pair(root: FiniteFieldElement) -> (FiniteFieldElement, FiniteFieldElement) {
+        let other = -&root;
+        if root.value <= other.value {
+            (root, other)
+        } else {
+            (other, root)
+        }
     }
 }
 
 impl Display for FiniteFieldElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.value);
+        }
         write!(f, "(value: {},modulus: {})", self.value, self.modulus)
     }
 }
 
+impl_field_operators!(FiniteFieldElement);
+
+impl std::ops::Neg for &FiniteFieldElement {
+    type Output = FiniteFieldElement;
+    fn neg(self) -> FiniteFieldElement {
+        let value = if self.value.is_zero() {
+            BigUint::zero()
+        } else {
+            &self.modulus - &self.value
+        };
+        FiniteFieldElement::from_reduced(value, self.modulus.clone())
+    }
+}
+
+impl std::ops::Neg for FiniteFieldElement {
+    type Output = FiniteFieldElement;
+    fn neg(self) -> FiniteFieldElement {
+        -&self
+    }
+}
+
+impl Field for FiniteFieldElement {
+    type Parent = PrimeField;
+
+    fn field(&self) -> PrimeField {
+        PrimeField {
+            modulus: self.modulus.clone(),
+        }
+    }
+
+    fn zero(&self) -> FiniteFieldElement {
+        FiniteFieldElement::new(0, self.modulus.clone())
+    }
+
+    fn one(&self) -> FiniteFieldElement {
+        FiniteFieldElement::new(1, self.modulus.clone())
+    }
+
+    fn integer(&self, n: i64) -> FiniteFieldElement {
+        FiniteFieldElement::new(n, self.modulus.clone())
+    }
+
+    fn inv(&self) -> Result<FiniteFieldElement, String> {
+        self.one().div(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    fn successor(&self) -> Option<FiniteFieldElement> {
+        let value = &self.value + &BigUint::one();
+        (value < self.modulus).then(|| FiniteFieldElement::from_reduced(value, self.modulus.clone()))
+    }
+
+    // both values have the width of the modulus, so the swap leaves their number of limbs as it was
+    fn conditional_swap(&mut self, other: &mut FiniteFieldElement, choice: bool) {
+        BigUint::conditional_swap(&mut self.value, &mut other.value, choice);
+    }
+
+    // the smaller of the two roots of sqrt
+    fn square_root(&self) -> Option<FiniteFieldElement> {
+        FiniteFieldElement::sqrt(self).map(|(root, _)| root)
+    }
+}
+
+// jacobi symbol (a / n) for an odd positive n, computed with quadratic reciprocity
+// algorithm:
+// 1. a <- a mod n, t <- 1
+// 2. while a != 0
+// 3.   while a is even: a <- a / 2, and if n = 3 or 5 mod 8 then t <- -t   ((2 / n) = -1)
+// 4.   swap a and n, and if a = n = 3 mod 4 then t <- -t                  (reciprocity)
+// 5.   a <- a mod n
+// 6. return t if n = 1, otherwise 0 (a and n are not coprimes)
+pub fn jacobi(a: &BigInt, n: &BigUint) -> Result<i8, String> {
+    if n.is_even() {
+        return Err(format!("Jacobi symbol needs an odd modulus, got {}", n));
+    }
+    let mut a = a.rem_euclid(n);
+    let mut n = n.clone();
+    let mut t = 1;
+    while !a.is_zero() {
+        while a.is_even() {
+            a = &a >> 1;
+            let r = n.limbs()[0] % 8;
+            if r == 3 || r == 5 {
+                t = -t;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a.limbs()[0] % 4 == 3 && n.limbs()[0] % 4 == 3 {
+            t = -t;
+        }
+        a = &a % &n;
+    }
+    Ok(if n.is_one() { t } else { 0 })
+}
+
+// legendre symbol (a / p) for an odd prime p: 1 if a is a non zero square mod p, -1 if it is not a square, 0 if p divides a
+// for a prime modulus it is the same as the jacobi symbol
+pub fn legendre(a: &BigInt, p: &BigUint) -> Result<i8, String> {
+    jacobi(a, p)
+}
+
 // struct to calculate the gcd of two numbers using the extended euclidean algorithm
 struct ExtendedEuclideanAlgorithm;
 
@@ -98,12 +413,19 @@ impl ExtendedEuclideanAlgorithm {
     // 6.   y_i+1 <= y_i-1 - q_i * y_i
     // 7.   i <- i + 1
     // 8. return r_i-1, x_i-1, y_i-1
-    fn extended_gcd(a: i32, b: i32) -> (i32, i32, i32) {
-        if b == 0 { // when remainder is 0, we have found the gcd
-            return (a, 1, 0);
+    fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+        let (mut r0, mut r1) = (a.clone(), b.clone());
+        let (mut x0, mut x1) = (BigInt::one(), BigInt::zero());
+        let (mut y0, mut y1) = (BigInt::zero(), BigInt::one());
+        while !r1.is_zero() { // when remainder is 0, we have found the gcd
+            let (q, r2) = r0.div_rem(&r1);
+            let x2 = &x0 - &(&q * &x1);
+            let y2 = &y0 - &(&q * &y1);
+            (r0, r1) = (r1, r2);
+            (x0, x1) = (x1, x2);
+            (y0, y1) = (y1, y2);
         }
-        let (d, s, t) = ExtendedEuclideanAlgorithm::extended_gcd(b, a % b);
-        (d, t , s - (a / b) * t)
+        (r0, x0, y0)
     }
 }
 
@@ -171,12 +493,179 @@ mod tests {
         assert_eq!(a.pow(3).unwrap(), FiniteFieldElement::new(6, 7));
     }
 
+    #[test]
+    fn test_pow_zero_exponent() {
+        let a = FiniteFieldElement::new(5, 7);
+        let zero = FiniteFieldElement::new(0, 7);
+        assert_eq!(a.pow(0).unwrap(), FiniteFieldElement::new(1, 7));
+        assert_eq!(zero.pow(0).unwrap(), FiniteFieldElement::new(1, 7));
+        assert_eq!(zero.pow(5).unwrap(), zero);
+    }
+
+    #[test]
+    fn test_pow_exponent_bigger_than_modulus() {
+        // a^(p-1) = 1 so a^(k(p-1) + r) = a^r
+        let a = FiniteFieldElement::new(5, 1021);
+        assert_eq!(a.pow(1020 * 1_000_000 + 3).unwrap(), a.pow(3).unwrap());
+        let huge = BigInt::from(BigUint::one() << 300);
+        assert_eq!(FiniteFieldElement::new(1, 1021).pow(huge).unwrap(), FiniteFieldElement::new(1, 1021));
+    }
+
+    #[test]
+    fn test_pow_negative_exponent() {
+        let a = FiniteFieldElement::new(5, 7);
+        assert_eq!(a.pow(-1).unwrap(), FiniteFieldElement::new(1, 7).div(&a).unwrap());
+        assert_eq!(a.pow(-3).unwrap().mul(&a.pow(3).unwrap()).unwrap(), FiniteFieldElement::new(1, 7));
+        assert_eq!(FiniteFieldElement::new(0, 7).pow(-1).unwrap_err(), "Zero division");
+    }
+
+    // roots of every element found by trying all the values
+    fn exhaustive_sqrt(a: &FiniteFieldElement) -> Option<(FiniteFieldElement, FiniteFieldElement)> {
+        let p = a.modulus.to_u64().unwrap() as i64;
+        let roots: Vec<FiniteFieldElement> = (0..p)
+            .map(|y| FiniteFieldElement::new(y, p))
+            .filter(|y| &y.square() == a)
+            .collect();
+        match roots.len() {
+            0 => None,
+            1 => Some((roots[0].clone(), roots[0].clone())),
+            _ => Some((roots[0].clone(), roots[1].clone())),
+        }
+    }
+
+    #[test]
+    fn test_sqrt_small_primes() {
+        // covers p = 3 mod 4, p = 5 mod 8 and p = 1 mod 8 with several powers of two in p - 1
+        for p in [2, 3, 5, 7, 11, 13, 17, 41, 43, 73, 97, 113, 193, 257, 1021] {
+            for v in 0..p {
+                let a = FiniteFieldElement::new(v, p);
+                let expected = exhaustive_sqrt(&a);
+                assert_eq!(a.sqrt(), expected, "sqrt of {} mod {}", v, p);
+                assert_eq!(a.sqrt_tonelli_shanks(), expected, "tonelli-shanks of {} mod {}", v, p);
+                assert_eq!(a.sqrt_cipolla(), expected, "cipolla of {} mod {}", v, p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_big_primes() {
+        // P-224 prime 2^224 - 2^96 + 1 has p - 1 divisible by 2^96, the worst case for tonelli-shanks
+        let p224 = (BigUint::one() << 224) - (BigUint::one() << 96) + BigUint::one();
+        for p in [p256(), p521(), p224] {
+            let root = FiniteFieldElement::new(BigUint::from(0x1234_5678_9abc_def0u64) << 100, p.clone());
+            let square = root.square();
+            let expected = FiniteFieldElement::root_pair(root);
+            assert_eq!(square.sqrt(), Some(expected.clone()));
+            assert_eq!(square.sqrt_tonelli_shanks(), Some(expected.clone()));
+            assert_eq!(square.sqrt_cipolla(), Some(expected));
+            // -1 is a square exactly when p = 1 mod 4
+            let minus_one = FiniteFieldElement::new(-1, p.clone());
+            assert_eq!(minus_one.sqrt().is_some(), p.limbs()[0] % 4 == 1);
+        }
+    }
+
+    #[test]
+    fn test_jacobi() {
+        let symbol = |a: i64, n: u32| jacobi(&BigInt::from(a), &BigUint::from(n)).unwrap();
+        assert_eq!(symbol(1001, 9907), -1);
+        assert_eq!(symbol(19, 45), 1);
+        assert_eq!(symbol(8, 21), -1);
+        assert_eq!(symbol(5, 21), 1);
+        assert_eq!(symbol(6, 15), 0);
+        assert_eq!(symbol(-1, 7), -1);
+        assert_eq!(symbol(-1, 13), 1);
+        assert!(jacobi(&BigInt::from(3), &BigUint::from(8u32)).is_err());
+    }
+
+    #[test]
+    fn test_legendre_matches_euler_criterion() {
+        for p in [3, 43, 1021] {
+            let half = (p - 1) / 2;
+            for v in 0..p {
+                let a = FiniteFieldElement::new(v, p);
+                let euler = a.pow(half).unwrap();
+                let expected = if v == 0 { 0 } else if euler.value.is_one() { 1 } else { -1 };
+                assert_eq!(a.legendre().unwrap(), expected, "({} / {})", v, p);
+            }
+        }
+        // big prime: squares have symbol 1, p = 3 mod 4 so their negatives have symbol -1
+        let square = FiniteFieldElement::new(BigUint::one() << 300, p521()).square();
+        assert_eq!(square.legendre().unwrap(), 1);
+        assert_eq!((-square).legendre().unwrap(), -1);
+    }
+
     #[test]
     fn test_div_not_coprime() {
-        let a = FiniteFieldElement::new(1, 7);
-        let b = FiniteFieldElement::new(-1, 7);
+        let a = FiniteFieldElement::new(1, 8);
+        let b = FiniteFieldElement::new(-2, 8);
+
+        assert_eq!(a.div(&b).unwrap_err(), "1 and 8 are not coprimes");
+    }
+
+    #[test]
+    fn test_new_negative_value() {
+        assert_eq!(FiniteFieldElement::new(-1, 7), FiniteFieldElement::new(6, 7));
+        assert_eq!(FiniteFieldElement::new(-15, 7), FiniteFieldElement::new(6, 7));
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = FiniteFieldElement::new(5, 7);
+        let b = FiniteFieldElement::new(3, 7);
+
+        assert_eq!(&a + &b, a.add(&b).unwrap());
+        assert_eq!(&a - &b, a.sub(&b).unwrap());
+        assert_eq!(&a * &b, a.mul(&b).unwrap());
+        assert_eq!(&a / &b, a.div(&b).unwrap());
+        assert_eq!(-&a, FiniteFieldElement::new(2, 7));
+        assert_eq!(-FiniteFieldElement::new(0, 7), FiniteFieldElement::new(0, 7));
+        // owned and borrowed operands can be mixed
+        assert_eq!(a.clone() * &a - b.clone() - &b, FiniteFieldElement::new(5 * 5 - 3 - 3, 7));
+        assert_eq!(&a * (a.clone() + b.clone()), FiniteFieldElement::new(5 * 8, 7));
+    }
 
-        assert_eq!(a.div(&b).unwrap_err(), "1 and 7 are not coprimes");
+    #[test]
+    fn test_assign_operators() {
+        let b = FiniteFieldElement::new(3, 7);
+        let mut a = FiniteFieldElement::new(5, 7);
+        a += &b;
+        assert_eq!(a, FiniteFieldElement::new(1, 7));
+        a -= b.clone();
+        assert_eq!(a, FiniteFieldElement::new(5, 7));
+        a *= &b;
+        assert_eq!(a, FiniteFieldElement::new(1, 7));
+        a /= b;
+        assert_eq!(a, FiniteFieldElement::new(5, 7));
+    }
+
+    #[test]
+    #[should_panic(expected = "Modulus mismatch")]
+    fn test_operator_modulus_mismatch() {
+        let _ = FiniteFieldElement::new(5, 7) + FiniteFieldElement::new(5, 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "Zero division")]
+    fn test_operator_zero_division() {
+        let _ = FiniteFieldElement::new(5, 7) / FiniteFieldElement::new(0, 7);
+    }
+
+    #[test]
+    fn test_prime_field() {
+        let field = PrimeField::new(1021).unwrap();
+        assert_eq!(field.element(-1), FiniteFieldElement::new(1020, 1021));
+        assert_eq!(field.to_string(), "F_1021");
+        assert_eq!(PrimeField::new(1020).unwrap_err(), "Modulus 1020 is not prime");
+        assert_eq!(PrimeField::new(0).unwrap_err(), "Modulus must be greater than 1, got 0");
+        assert_eq!(PrimeField::new(-7).unwrap_err(), "Modulus must be greater than 1, got -7");
+        assert!(PrimeField::new(p521()).is_ok());
+    }
+
+    #[test]
+    fn test_new_checked() {
+        assert_eq!(FiniteFieldElement::new_checked(8, 7).unwrap(), FiniteFieldElement::new(1, 7));
+        assert_eq!(FiniteFieldElement::new_checked(1, 561).unwrap_err(), "Modulus 561 is not prime");
+        assert!(FiniteFieldElement::new_checked(1, 0).is_err());
     }
 
     #[test]
@@ -188,4 +677,114 @@ mod tests {
         assert_eq!(a.check_modulus(&b).unwrap(), ());
         assert_eq!(a.check_modulus(&c).unwrap_err(), "Modulus mismatch");
     }
-}
\ No newline at end of file
+
+    // NIST P-256 prime 2^256 - 2^224 + 2^192 + 2^96 - 1
+    fn p256() -> BigUint {
+        "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap()
+    }
+
+    // NIST P-521 prime 2^521 - 1
+    fn p521() -> BigUint {
+        (BigUint::one() << 521) - BigUint::one()
+    }
+
+    #[test]
+    fn test_big_modulus_add_sub() {
+        for p in [p256(), p521()] {
+            let minus_one = FiniteFieldElement::new(-1, p.clone());
+            let one = FiniteFieldElement::new(1, p.clone());
+            assert_eq!(minus_one.value, &p - &BigUint::one());
+            assert_eq!(minus_one.add(&one).unwrap(), FiniteFieldElement::new(0, p.clone()));
+            assert_eq!(one.sub(&minus_one).unwrap(), FiniteFieldElement::new(2, p.clone()));
+        }
+    }
+
+    #[test]
+    fn test_big_modulus_mul() {
+        for p in [p256(), p521()] {
+            // (-1) * (-1) = 1 needs the full double width product before reducing
+            let minus_one = FiniteFieldElement::new(-1, p.clone());
+            assert_eq!(minus_one.mul(&minus_one).unwrap(), FiniteFieldElement::new(1, p.clone()));
+
+            // 2^(bits - 1) * 2 = 2^bits
+            let bits = p.bits();
+            let half = FiniteFieldElement::new(BigUint::one() << (bits - 1), p.clone());
+            let two = FiniteFieldElement::new(2, p.clone());
+            let expected = FiniteFieldElement::new(BigUint::one() << bits, p.clone());
+            assert_eq!(half.mul(&two).unwrap(), expected);
+        }
+        // 2^521 = 1 mod 2^521 - 1
+        let x = FiniteFieldElement::new(BigUint::one() << 260, p521());
+        let y = FiniteFieldElement::new(BigUint::one() << 261, p521());
+        assert_eq!(x.mul(&y).unwrap(), FiniteFieldElement::new(1, p521()));
+    }
+
+    #[test]
+    fn test_big_modulus_div() {
+        for p in [p256(), p521()] {
+            let a: BigUint = "0x6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296".parse().unwrap();
+            let b: BigUint = "0x4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5".parse().unwrap();
+            let a = FiniteFieldElement::new(a, p.clone());
+            let b = FiniteFieldElement::new(b, p.clone());
+            let q = a.div(&b).unwrap();
+            assert_eq!(q.mul(&b).unwrap(), a);
+        }
+    }
+
+    #[test]
+    fn test_conditional_swap_width() {
+        // a one limb value and a full width one keep the width of the modulus whatever the choice
+        let p = p521();
+        let widths = [false, true].map(|choice| {
+            let mut a = FiniteFieldElement::new(1, p.clone());
+            let mut b = FiniteFieldElement::new(-1, p.clone());
+            a.conditional_swap(&mut b, choice);
+            assert_eq!((a.value == BigUint::one(), b.value == BigUint::one()), (!choice, choice));
+            (a.value.limbs().len(), b.value.limbs().len())
+        });
+        assert_eq!(widths, [(p.limbs().len(), p.limbs().len()); 2]);
+    }
+
+    #[test]
+    fn test_big_modulus_pow() {
+        let a = FiniteFieldElement::new(BigUint::one() << 200, p256());
+        let a5 = a.mul(&a).unwrap().mul(&a).unwrap().mul(&a).unwrap().mul(&a).unwrap();
+        assert_eq!(a.pow(5).unwrap(), a5);
+    }
+
+    #[test]
+    fn test_fermat_inversion() {
+        // a^(p-2) = a^-1 for prime p
+        for p in [p256(), p521()] {
+            let a = FiniteFieldElement::new(BigUint::from(0xdeadbeefu64) << 100, p.clone());
+            let one = FiniteFieldElement::new(1, p.clone());
+            let p_minus_one = BigInt::from(&p - &BigUint::one());
+            let p_minus_two = BigInt::from(&p - &BigUint::from(2u32));
+            assert_eq!(a.pow(p_minus_one).unwrap(), one);
+            let inverse = a.pow(p_minus_two).unwrap();
+            assert_eq!(inverse.mul(&a).unwrap(), one);
+            assert_eq!(inverse, one.div(&a).unwrap());
+            assert_eq!(inverse, a.pow(-1).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_euler_criterion() {
+        // a^((p-1)/2) is 1 for quadratic residues and -1 for non residues
+        let p = p256();
+        let half = BigInt::from(&(&p - &BigUint::one()) >> 1);
+        let one = FiniteFieldElement::new(1, p.clone());
+        let minus_one = FiniteFieldElement::new(-1, p.clone());
+        let square = FiniteFieldElement::new(123456789, p.clone()).pow(2).unwrap();
+        assert_eq!(square.pow(half.clone()).unwrap(), one);
+        // p = 3 mod 4 so -1 is not a square
+        assert_eq!(minus_one.pow(half.clone()).unwrap(), minus_one);
+        // with p = 1021 = 5 mod 8, 2 is not a square and the residues are exactly the squares
+        let residues: Vec<i64> = (1..1021).filter(|x| {
+            FiniteFieldElement::new(*x, 1021).pow(510).unwrap() == FiniteFieldElement::new(1, 1021)
+        }).collect();
+        assert_eq!(residues.len(), 510);
+        assert!(!residues.contains(&2));
+        assert!(residues.iter().all(|r| (1..1021).any(|y: i64| (y * y) % 1021 == *r)));
+    }
+}
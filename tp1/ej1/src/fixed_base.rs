@@ -0,0 +1,135 @@
+use crate::big_int::{BigInt, BigUint};
+use crate::field::Field;
+use crate::finite_field_element::FiniteFieldElement;
+use crate::point::Point;
+use crate::projective::ProjectivePoint;
+
+// window of the tables of the domain parameters, 16 points per window
+pub const FIXED_BASE_WINDOW: usize = 4;
+
+// fixed window precomputation for multiples of a fixed point G of odd order n: k in [0, n) is split in
+// windows of w bits k = sum of d_j 2^(wj), and row j of the table holds d 2^(wj) G for every digit
+// 0 <= d < 2^w, so kG = sum of the table entries of the digits without any doubling.
+// the entries are projective points added with the complete formulas, which are exception free in the
+// subgroup of odd order generated by G, and each entry is read scanning the whole row with conditional
+// swaps: the sequence of field operations and of entries read doesn't depend on k, although the
+// BigUint arithmetic under the field operations is not constant time
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedBaseTable<F: Field = FiniteFieldElement> {
+    window: usize,
+    order: BigUint,
+    rows: Vec<Vec<ProjectivePoint<F>>>,
+}
+
+impl<F: Field> FixedBaseTable<F> {
+    // ceil(bits of n / w) rows of 2^w points, built with one complete addition per point.
+    // the order must be the order of the generator (or a multiple of it) and odd
+    pub fn new(generator: &Point<F>, order: &BigUint, window: usize) -> Result<FixedBaseTable<F>, String> {
+        if !(1..=8).contains(&window) {
+            return Err(format!("Window width must be between 1 and 8, got {}", window));
+        }
+        if !order.is_odd() {
+            return Err(format!("Fixed base tables need an odd order, got {}", order));
+        }
+        let infinity = ProjectivePoint::from_affine(&Point::new(None, None, generator.curve.clone())?)?;
+        let mut base = ProjectivePoint::from_affine(generator)?;
+        let mut rows = Vec::new();
+        for _ in 0..order.bits().div_ceil(window) {
+            // 0, B, 2B, ..., (2^w - 1)B and then 2^w B is the base of the next row
+            let mut row = vec![infinity.clone()];
+            for d in 1..1 << window {
+                row.push(row[d - 1].add(&base)?);
+            }
+            base = row[row.len() - 1].add(&base)?;
+            rows.push(row);
+        }
+        Ok(FixedBaseTable {
+            window,
+            order: order.clone(),
+            rows,
+        })
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    // kG with one complete addition per window, k is reduced modulo the order first
+    pub fn mul<S: Into<BigInt>>(&self, scalar: S) -> Result<Point<F>, String> {
+        let scalar = scalar.into().rem_euclid(&self.order);
+        let mut product = self.rows[0][0].clone();
+        for (j, row) in self.rows.iter().enumerate() {
+            let digit = (0..self.window).fold(0, |digit, i| digit | (scalar.bit(j * self.window + i) as usize) << i);
+            product = product.add(&select(row, digit))?;
+        }
+        product.to_affine()
+    }
+}
+
+// row[index] going through every entry: the result starts as row[0] and each entry is swapped in when its
+// index matches, so every call does the same conditional swaps
+fn select<F: Field>(row: &[ProjectivePoint<F>], index: usize) -> ProjectivePoint<F> {
+    let mut selected = row[0].clone();
+    for (i, entry) in row.iter().enumerate().skip(1) {
+        let mut entry = entry.clone();
+        let choice = i == index;
+        selected.x.conditional_swap(&mut entry.x, choice);
+        selected.y.conditional_swap(&mut entry.y, choice);
+        selected.z.conditional_swap(&mut entry.z, choice);
+    }
+    selected
+}
+
+// tests for the fixed base tables against the scalar multiplication of the point
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_curve::EllipticCurve;
+
+    #[test]
+    fn test_mul() {
+        // point of prime order 1039
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let g = Point::new(Some(FiniteFieldElement::new(379, 1021)), Some(FiniteFieldElement::new(1011, 1021)), curve).unwrap();
+        let order = BigUint::from(1039u32);
+        for window in [1, 3, 4, 5] {
+            let table = FixedBaseTable::new(&g, &order, window).unwrap();
+            assert_eq!(table.rows.len(), 11usize.div_ceil(window));
+            for k in [0, 1, 2, 15, 16, 17, 655, 1038, 1039, 1040, 5000, -1, -655] {
+                assert_eq!(table.mul(k).unwrap(), g.scalar_mul(k).unwrap(), "k = {}, w = {}", k, window);
+            }
+        }
+        let table = FixedBaseTable::new(&g, &order, 4).unwrap();
+        for k in 0..1039 {
+            assert_eq!(table.mul(k).unwrap(), g.scalar_mul(k).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_full_size() {
+        // P-256 generator and order
+        let p: BigUint = "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap();
+        let b: BigUint = "0x5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b".parse().unwrap();
+        let n: BigUint = "0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551".parse().unwrap();
+        let gx: BigUint = "0x6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296".parse().unwrap();
+        let gy: BigUint = "0x4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5".parse().unwrap();
+        let curve = EllipticCurve::new(-3, b, p.clone()).unwrap();
+        let g = Point::new(Some(FiniteFieldElement::new(gx, p.clone())), Some(FiniteFieldElement::new(gy, p)), curve).unwrap();
+        let table = FixedBaseTable::new(&g, &n, 4).unwrap();
+        let k: BigUint = "0xc51e4753afdec1e6b6c6a5b992f43f8dd0c7a8933072708b6522468b2ffb06fd".parse().unwrap();
+        assert_eq!(table.mul(&k).unwrap(), g.scalar_mul(&k).unwrap());
+        assert!(table.mul(&n).unwrap().is_infinity());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let curve = EllipticCurve::new(-1, 0, 43).unwrap();
+        let g = curve.points().nth(1).unwrap();
+        assert!(FixedBaseTable::new(&g, &BigUint::from(44u32), 4).unwrap_err().contains("odd order"));
+        assert!(FixedBaseTable::new(&g, &BigUint::from(11u32), 0).is_err());
+        // the complete formulas need a short weierstrass curve
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap();
+        let g = curve.points().nth(1).unwrap();
+        assert!(FixedBaseTable::new(&g, &curve.order().unwrap(), 4).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt::{Display, Formatter, self};
+
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::finite_field_element::FiniteFieldElement;
+use crate::point::Point;
+
+// point of a short weierstrass curve in jacobian coordinates (X : Y : Z), the affine point (X / Z^2, Y / Z^3).
+// the curve becomes Y^2 = X^3 + a X Z^4 + b Z^6 and the point at infinity is any point with Z = 0.
+// doubling and addition need no inversion, only the conversion back to affine coordinates does.
+// add, add_mixed and double branch on infinity and on points with the same x, like Point::add
+#[derive(Debug, Clone)]
+pub struct JacobianPoint<F: Field = FiniteFieldElement> {
+    pub x: F,
+    pub y: F,
+    pub z: F,
+    pub curve: EllipticCurve<F>,
+}
+
+impl<F: Field> JacobianPoint<F> {
+    // (x : y : 1), or (1 : 1 : 0) for the point at infinity
+    pub fn from_affine(point: &Point<F>) -> Result<JacobianPoint<F>, String> {
+        let curve = &point.curve;
+        if !curve.is_short() {
+            return Err("Jacobian coordinates need a curve in short weierstrass form".to_string());
+        }
+        let (x, y, z) = match (&point.x, &point.y) {
+            (Some(x), Some(y)) => (x.clone(), y.clone(), x.one()),
+            _ => (curve.a4.one(), curve.a4.one(), curve.a4.zero()),
+        };
+        Ok(JacobianPoint {
+            x,
+            y,
+            z,
+            curve: curve.clone(),
+        })
+    }
+
+    // (X / Z^2, Y / Z^3) with a single inversion
+    pub fn to_affine(&self) -> Result<Point<F>, String> {
+        if self.is_infinity() {
+            return Point::new(None, None, self.curve.clone());
+        }
+        let z_inv = self.z.inv()?;
+        let z_inv2 = z_inv.square();
+        let x = self.x.clone() * &z_inv2;
+        let y = self.y.clone() * &z_inv2 * &z_inv;
+        Point::new(Some(x), Some(y), self.curve.clone())
+    }
+
+    // to_affine of several points with a single inversion (montgomery's trick): with the prefix products
+    // c_i = Z_0 ... Z_i, the inverse of c_n gives 1 / Z_i = c_(i-1) / c_i walking back from the last point.
+    // points at infinity are skipped
+    pub fn batch_to_affine(points: &[JacobianPoint<F>]) -> Result<Vec<Point<F>>, String> {
+        let finite: Vec<&JacobianPoint<F>> = points.iter().filter(|p| !p.is_infinity()).collect();
+        let mut products = Vec::with_capacity(finite.len());
+        for p in &finite {
+            let product = match products.last() {
+                Some(last) => p.z.clone() * last,
+                None => p.z.clone(),
+            };
+            products.push(product);
+        }
+        let mut z_inverses = vec![None; finite.len()];
+        if let Some(last) = products.last() {
+            let mut inverse = last.inv()?;
+            for i in (0..finite.len()).rev() {
+                z_inverses[i] = Some(match i {
+                    0 => inverse.clone(),
+                    _ => inverse.clone() * &products[i - 1],
+                });
+                inverse = inverse * &finite[i].z;
+            }
+        }
+        let mut z_inverses = z_inverses.into_iter();
+        points.iter()
+            .map(|p| {
+                if p.is_infinity() {
+                    return Point::new(None, None, p.curve.clone());
+                }
+                let z_inv = z_inverses.next().flatten().expect("Error in JacobianPoint::batch_to_affine inverse");
+                let z_inv2 = z_inv.square();
+                Point::new(Some(p.x.clone() * &z_inv2), Some(p.y.clone() * &z_inv2 * &z_inv), p.curve.clone())
+            })
+            .collect()
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub fn negate(&self) -> JacobianPoint<F> {
+        JacobianPoint {
+            y: -self.y.clone(),
+            ..self.clone()
+        }
+    }
+
+    // doubling, with Y = 0 (a point of order 2) Z3 = 0 gives infinity
+    // S = 4 X Y^2, M = 3X^2 + a Z^4
+    // X3 = M^2 - 2S, Y3 = M(S - X3) - 8Y^4, Z3 = 2YZ
+    pub fn double(&self) -> JacobianPoint<F> {
+        if self.is_infinity() {
+            return self.clone();
+        }
+        // the small multiples with additions, cheaper than a product
+        let double = |v: F| v.clone() + &v;
+        let yy = self.y.square();
+        let s = double(double(self.x.clone() * &yy));
+        let xx = self.x.square();
+        let m = double(xx.clone()) + &xx + self.curve.a4.clone() * self.z.square().square();
+        let x3 = m.square() - &s - &s;
+        let y3 = m * (s - &x3) - double(double(double(yy.square())));
+        let z3 = double(self.y.clone() * &self.z);
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+            curve: self.curve.clone(),
+        }
+    }
+
+    // sum of two jacobian points
+    // U1 = X1 Z2^2, U2 = X2 Z1^2, S1 = Y1 Z2^3, S2 = Y2 Z1^3, H = U2 - U1, r = S2 - S1
+    // X3 = r^2 - H^3 - 2 U1 H^2, Y3 = r(U1 H^2 - X3) - S1 H^3, Z3 = Z1 Z2 H
+    pub fn add(&self, other: &JacobianPoint<F>) -> Result<JacobianPoint<F>, String> {
+        self.check_curve(&other.curve)?;
+        if self.is_infinity() {
+            return Ok(other.clone());
+        }
+        if other.is_infinity() {
+            return Ok(self.clone());
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x.clone() * &z2z2;
+        let u2 = other.x.clone() * &z1z1;
+        let s1 = self.y.clone() * &other.z * &z2z2;
+        let s2 = other.y.clone() * &self.z * &z1z1;
+        Ok(self.add_with(u1, s1, u2, s2, self.z.clone() * &other.z))
+    }
+
+    // sum with an affine point, Z2 = 1 saves the products by Z2
+    // U2 = x2 Z1^2, S2 = y2 Z1^3, H = U2 - X1, r = S2 - Y1
+    // X3 = r^2 - H^3 - 2 X1 H^2, Y3 = r(X1 H^2 - X3) - Y1 H^3, Z3 = Z1 H
+    pub fn add_mixed(&self, other: &Point<F>) -> Result<JacobianPoint<F>, String> {
+        self.check_curve(&other.curve)?;
+        let (x2, y2) = match (&other.x, &other.y) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Ok(self.clone()),
+        };
+        if self.is_infinity() {
+            return JacobianPoint::from_affine(other);
+        }
+        let z1z1 = self.z.square();
+        let u2 = x2.clone() * &z1z1;
+        let s2 = y2.clone() * &self.z * &z1z1;
+        Ok(self.add_with(self.x.clone(), self.y.clone(), u2, s2, self.z.clone()))
+    }
+
+    // end of the addition from the coordinates scaled to the same Z, z is the product of the Z of the summands
+    fn add_with(&self, u1: F, s1: F, u2: F, s2: F, z: F) -> JacobianPoint<F> {
+        let h = u2 - &u1;
+        let r = s2 - &s1;
+        if h.is_zero() {
+            // same x: the same point or its inverse
+            return if r.is_zero() {
+                self.double()
+            } else {
+                JacobianPoint {
+                    x: z.one(),
+                    y: z.one(),
+                    z: z.zero(),
+                    curve: self.curve.clone(),
+                }
+            };
+        }
+        let hh = h.square();
+        let hhh = hh.clone() * &h;
+        let v = u1 * &hh;
+        let x3 = r.square() - &hhh - &v - &v;
+        let y3 = r * (v - &x3) - s1 * &hhh;
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z * &h,
+            curve: self.curve.clone(),
+        }
+    }
+
+    fn check_curve(&self, curve: &EllipticCurve<F>) -> Result<(), String> {
+        if !self.curve.eq(curve) {
+            return Err(format!("Points are not on the same curve: {} and {}", self.curve, curve));
+        }
+        Ok(())
+    }
+}
+
+// same affine point, (X1 : Y1 : Z1) = (X2 : Y2 : Z2) iff X1 Z2^2 = X2 Z1^2 and Y1 Z2^3 = Y2 Z1^3
+impl<F: Field> PartialEq for JacobianPoint<F> {
+    fn eq(&self, other: &JacobianPoint<F>) -> bool {
+        if self.is_infinity() || other.is_infinity() {
+            return self.is_infinity() && other.is_infinity() && self.curve.eq(&other.curve);
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        self.curve.eq(&other.curve)
+            && self.x.clone() * &z2z2 == other.x.clone() * &z1z1
+            && self.y.clone() * &other.z * &z2z2 == other.y.clone() * &self.z * &z1z1
+    }
+}
+
+impl<F: Field> Display for JacobianPoint<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Jacobian Point: ({} : {} : {})", self.x, self.y, self.z)
+    }
+}
+
+// tests for the jacobian coordinates against the affine group law
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime_field::F1021;
+
+    // the first points of the curve, enough for the group law tests
+    fn points(curve: &EllipticCurve<F1021>) -> Vec<Point<F1021>> {
+        curve.points().take(41).collect()
+    }
+
+    #[test]
+    fn test_affine_conversion() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let point = Point::new(Some(FiniteFieldElement::new(379, 1021)), Some(FiniteFieldElement::new(1011, 1021)), curve.clone()).unwrap();
+        let jacobian = JacobianPoint::from_affine(&point).unwrap();
+        assert_eq!(jacobian.to_affine().unwrap(), point);
+        let infinity = Point::new(None, None, curve).unwrap();
+        assert!(JacobianPoint::from_affine(&infinity).unwrap().is_infinity());
+        assert_eq!(JacobianPoint::from_affine(&infinity).unwrap().to_affine().unwrap(), infinity);
+
+        // the same point with another Z: (X l^2 : Y l^3 : l)
+        let l = FiniteFieldElement::new(17, 1021);
+        let scaled = JacobianPoint {
+            x: jacobian.x.clone() * l.square(),
+            y: jacobian.y.clone() * l.square() * &l,
+            z: l,
+            curve: jacobian.curve.clone(),
+        };
+        assert_eq!(scaled, jacobian);
+        assert_eq!(scaled.to_affine().unwrap(), point);
+
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap();
+        assert!(JacobianPoint::from_affine(&curve.points().nth(1).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_group_law() {
+        // y^2 = x^3 - x has points of order 2
+        for curve in [
+            EllipticCurve::from_coefficients(F1021::new(-3), F1021::new(-3)).unwrap(),
+            EllipticCurve::from_coefficients(F1021::new(-1), F1021::new(0)).unwrap(),
+        ] {
+            let points = points(&curve);
+            for p in &points {
+                let jacobian_p = JacobianPoint::from_affine(p).unwrap();
+                assert_eq!(jacobian_p.double().to_affine().unwrap(), p.add(p).unwrap());
+                assert_eq!(jacobian_p.negate().to_affine().unwrap(), p.negate());
+                for q in &points {
+                    let expected = p.add(q).unwrap();
+                    assert_eq!(jacobian_p.add_mixed(q).unwrap().to_affine().unwrap(), expected);
+                    // with both Z different from 1
+                    let doubled = jacobian_p.double();
+                    let jacobian_q = JacobianPoint::from_affine(q).unwrap().double();
+                    assert_eq!(doubled.add(&jacobian_q).unwrap().to_affine().unwrap(), p.add(p).unwrap().add(&q.add(q).unwrap()).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_to_affine() {
+        let curve = EllipticCurve::from_coefficients(F1021::new(-3), F1021::new(-3)).unwrap();
+        let points = points(&curve);
+        // doubled to get Z different from 1, the first one is the point at infinity
+        let jacobian: Vec<JacobianPoint<F1021>> = points.iter().map(|p| JacobianPoint::from_affine(p).unwrap().double()).collect();
+        let affine = JacobianPoint::batch_to_affine(&jacobian).unwrap();
+        assert_eq!(affine.len(), points.len());
+        for (p, q) in jacobian.iter().zip(&affine) {
+            assert_eq!(p.to_affine().unwrap(), *q);
+        }
+        assert!(affine[0].is_infinity());
+        assert!(JacobianPoint::<F1021>::batch_to_affine(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_different_curves() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let other = EllipticCurve::new(905, 100, 1021).unwrap();
+        let p = JacobianPoint::from_affine(&Point::new(None, None, curve).unwrap()).unwrap();
+        let q = Point::new(Some(FiniteFieldElement::new(1006, 1021)), Some(FiniteFieldElement::new(416, 1021)), other).unwrap();
+        assert!(p.add_mixed(&q).is_err());
+        assert!(p.add(&JacobianPoint::from_affine(&q).unwrap()).is_err());
+    }
+}
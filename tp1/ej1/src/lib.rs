@@ -0,0 +1,20 @@
+pub mod barrett;
+pub mod big_int;
+pub mod finite_field_element;
+pub mod elliptic_curve;
+pub mod extension_field;
+pub mod factorization;
+pub mod field;
+pub mod fixed_base;
+pub mod jacobian;
+pub mod mestre;
+pub mod montgomery;
+pub mod multi_scalar;
+pub mod point;
+pub mod polynomial;
+pub mod primality;
+pub mod prime_field;
+pub mod projective;
+pub mod schoof;
+pub mod diffie_hellman;
+pub mod domain_parameters;
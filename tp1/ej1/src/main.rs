@@ -1,19 +1,13 @@
-mod finite_field_element;
 use std::sync::{Arc, Mutex};
 
-use finite_field_element::FiniteFieldElement;
-
-mod elliptic_curve;
-use elliptic_curve::EllipticCurve;
-
-mod point;
-use point::Point;
-
-mod diffie_hellman;
-use crate::diffie_hellman::DiffieHellman;
-
-use rand::Rng;
+use ej1::finite_field_element::FiniteFieldElement;
+use ej1::elliptic_curve::EllipticCurve;
+use ej1::point::Point;
+use ej1::diffie_hellman::DiffieHellman;
+use ej1::domain_parameters::DomainParameters;
+use ej1::big_int::BigUint;
 
+#[allow(clippy::unnecessary_unwrap)]
 fn main() {
     // Ejercicio 1:
     // Implementar un tipo de dato para un elemento de cuerpo finito, junto con sus operaciones aritméticas fundamentales (adición, sustracción, multiplicación y división).
@@ -53,13 +47,15 @@ fn main() {
 
     println!("\nEjercicio 2:");
     // Se crea la curva eliptica y el punto p generador de la curva
-    let curve = EllipticCurve::new(-3.0, -3.0);
+    let curve = EllipticCurve::new(-3, -3, 1021).expect("Error in EllipticCurve::new");
     let p = Point::new(
         Some(FiniteFieldElement::new(379, 1021)),
         Some(FiniteFieldElement::new(1011, 1021)),
         curve.clone()
     ).expect("Error in Point::new");
 
+    // kP con la forma NAF de ancho 4 de k (655 = 3 * 256 - 7 * 16 - 1): se precalculan P, 3P, 5P y 7P y quedan
+    // 8 duplicaciones y 2 sumas, en vez de 9 duplicaciones y 5 sumas con double-and-add o 654 sumas
     let k_p = p.scalar_mul(655).unwrap();
     println!("kP = {}", k_p);
 
@@ -67,28 +63,25 @@ fn main() {
     // p + 1 - 2sqrt(p) <= #E(Zp) <= p + 1 + 2sqrt(p) donde #E(Zp) es la cantidad de puntos de la curva
     // con p = 1021 queda: 1021 + 1 - 2sqrt(1021) <= #E(Zp) <= 1021 + 1 + 2sqrt(1021)
 
-    // Se calcula la cantidad de puntos de la curva de forma naive (por cuestion de simplicidad ya que sino se deberia usar el algoritmo de Schoof)
-    let mut count = 0;
-    for x in 0..1021 {
-        for y in 0..1021 {
-            let point = Point::new(
-                Some(FiniteFieldElement::new(x, 1021)),
-                Some(FiniteFieldElement::new(y, 1021)),
-                curve.clone()
-            );
-            if point.is_ok() {
-                count += 1;
-            }
-        }
-    }
-    // debemos sumar 1 por el punto en el infinito
-    count += 1;
+    // Se calcula la cantidad de puntos de la curva con el algoritmo de Schoof: se obtiene la traza t del
+    // endomorfismo de Frobenius modulo primos chicos l (con los polinomios de division) y se reconstruye t con
+    // el teorema chino del resto, #E = p + 1 - t. Esto escala a primos de cientos de bits, a diferencia de
+    // probar todos los pares (x, y) que es O(p^2) o sumar simbolos de Legendre que es O(p)
+    let count = curve.order().expect("Error counting points")
+        .to_u64().expect("Error converting the number of points");
     println!("Cantidad de puntos de la curva: {}", count);
 
     // con hasse sabemos que debe estar entre min y max
     let min = 1021.0 + 1.0 - 2.0 * 1021.0_f64.sqrt();
     let max = 1021.0 + 1.0 + 2.0 * 1021.0_f64.sqrt();
     println!("El orden calculado chequea Hasse? {} <= #E(Zp) <= {} = {}", min, max, min <= count as f64 && count as f64 <= max);
+
+    // Alternativa para primos medianos (hasta ~60 bits): baby-step giant-step de Mestre, se busca el orden de puntos
+    // random dentro del intervalo de Hasse y se usa la curva twist cuando el orden no alcanza para determinar #E
+    let count_bsgs = curve.order_bsgs().expect("Error counting points with baby-step giant-step")
+        .to_u64().expect("Error converting the number of points");
+    println!("Cantidad de puntos con baby-step giant-step (Mestre): {}", count_bsgs);
+    println!("El orden con baby-step giant-step chequea Hasse? {}", min <= count_bsgs as f64 && count_bsgs as f64 <= max);
     
     
     // Ejercicio 3:
@@ -96,7 +89,9 @@ fn main() {
     // Usar la curva con p=43, y2=x3+6 y como generador g=(13,15). ¿Qué sucede si se emplea el punto g=(9,2)?
 
     // RTA: creamos dos threads para simular dos usuarios con un channel cada uno para comunicarse
-    // Vemos que las claves generadas son distintas, por lo que se debe acordar el punto generador
+    // Con distintos generadores las claves generadas son distintas, por lo que se debe acordar el punto generador.
+    // La curva tiene 39 = 3 * 13 puntos y el grupo es ciclico Z/39: g=(13,15) tiene orden 13 (cofactor 3) y solo
+    // genera un subgrupo de 13 puntos, mientras que g=(9,2) tiene orden 39 y genera todo el grupo
 
     println!("\nEjercicio 3:");
     
@@ -104,7 +99,17 @@ fn main() {
     let (tx_bob, rx_alice) = std::sync::mpsc::channel::<Point>();
 
     // se crea la curva eliptica y el punto generador
-    let curve = EllipticCurve::new(0.0, 6.0);
+    let curve = EllipticCurve::new(0, 6, 43).expect("Error in EllipticCurve::new");
+
+    // puntos de la curva, con una raiz cuadrada por cada x en vez de probar todos los pares (x, y)
+    let points: Vec<String> = curve.points()
+        .map(|point| match (point.x, point.y) {
+            (Some(x), Some(y)) => format!("({}, {})", x.value, y.value),
+            _ => "O".to_string(),
+        })
+        .collect();
+    println!("Puntos de la curva ({}): {}", points.len(), points.join(" "));
+
     let generator = Point::new(
         Some(FiniteFieldElement::new(13, 43)),
         Some(FiniteFieldElement::new(15, 43)),
@@ -117,30 +122,47 @@ fn main() {
         curve.clone()
     ).expect("Error in Point::new generator");
 
+    // estructura del grupo y orden de cada generador (el menor n con nG = O)
+    let structure = curve.group_structure().expect("Error in EllipticCurve::group_structure");
+    println!("Estructura del grupo: E(F_43) = {}", structure);
+    for (name, g) in [("g=(13,15)", &generator), ("g=(9,2)", &generator2)] {
+        let order = g.order().expect("Error in Point::order");
+        let cofactor = g.cofactor().expect("Error in Point::cofactor");
+        println!("El generador {} tiene orden {} y cofactor {}", name, order, cofactor);
+    }
+
+    // el acuerdo de clave solo acepta parametros de dominio validados: generador en la curva, de orden n primo
+    // y h * n = #E. Con g=(9,2) el orden 39 no es primo y la validacion falla, el subgrupo de orden 3 permitiria
+    // averiguar el secreto modulo 3 (ataque de subgrupo chico)
+    let parameters = DomainParameters::new(curve.clone(), generator.clone(), BigUint::from(13u32), BigUint::from(3u32))
+        .validate()
+        .expect("Error in DomainParameters::validate");
+    println!("Parametros de dominio validos: {}", parameters);
+    match DomainParameters::new(curve.clone(), generator2.clone(), BigUint::from(39u32), BigUint::one()).validate() {
+        Ok(_) => println!("Parametros de dominio con g=(9,2) validos"),
+        Err(e) => println!("Parametros de dominio con g=(9,2) invalidos: {}", e),
+    }
+
     let mut handles = vec!();
     // se crea el thread de alice
     {
         let tx_alice_clone = tx_alice.clone();
-        let generator = generator.clone();
-        let generator2 = generator2.clone();
+        let parameters = parameters.clone();
         let rx_alice = Arc::new(Mutex::new(rx_alice));
         let _alice_thread = std::thread::spawn(move || {
-            // generar un numero random entre 1 y 100
+            // generar el secreto, un numero random entre 1 y n - 1 (un multiplo de n daria el punto en el infinito)
             let mut rng = rand::thread_rng();
-            let random_number = rng.gen_range(1..100);
+            let random_number = parameters.random_secret(&mut rng);
             println!("Random number Alice: {}", random_number);
 
             {
                 let rx = rx_alice.clone();
                 // alice calcula su punto publico con el numero random ( A = a * g )
-                let shared_key = DiffieHellman::calculate_shared_key( &generator,random_number.clone(), tx_alice_clone.clone(), rx);
-                println!("Shared key from alice first generator: {}", shared_key);
-            }
-            {
-                let rx = rx_alice.clone();
-                // generamos otra clave con el otro generador
-                let shared_key = DiffieHellman::calculate_shared_key( &generator2, random_number.clone(),tx_alice_clone.clone(), rx);
-                println!("Shared key from alice with second generator: {}", shared_key);
+                let shared_key = DiffieHellman::calculate_shared_key( &parameters,&random_number, tx_alice_clone.clone(), rx);
+                match shared_key {
+                    Ok(shared_key) => println!("Shared key from alice: {}", shared_key),
+                    Err(e) => println!("Error en el acuerdo de clave de alice: {}", e),
+                }
             }
         });
         handles.push(_alice_thread);
@@ -150,25 +172,22 @@ fn main() {
     // se crea el thread de bob
     {
         let tx_bob_clone = tx_bob.clone();
-        let generator = generator.clone();
+        let parameters = parameters.clone();
         let rx_bob = Arc::new(Mutex::new(rx_bob));
         let _bob_thread = std::thread::spawn(move || {
-            // generar un numero random entre 1 y 100
+            // generar el secreto, un numero random entre 1 y n - 1 (un multiplo de n daria el punto en el infinito)
             let mut rng = rand::thread_rng();
-            let random_number = rng.gen_range(1..100);
+            let random_number = parameters.random_secret(&mut rng);
             println!("Random number Bob: {}", random_number);
 
             {
                 let rx = rx_bob.clone();
                 // bob calcula su punto publico con el numero random ( B = b * g )
-                let shared_key = DiffieHellman::calculate_shared_key(&generator,random_number.clone(),tx_bob_clone.clone(), rx);
-                println!("Shared key from bob first generator: {}", shared_key);
-            }
-            {
-                let rx = rx_bob.clone();
-                // generamos otra clave con el otro generador
-                let shared_key = DiffieHellman::calculate_shared_key( &generator2, random_number.clone(),tx_bob_clone.clone(), rx);
-                println!("Shared key from bob with second generator: {}", shared_key);
+                let shared_key = DiffieHellman::calculate_shared_key(&parameters,&random_number,tx_bob_clone.clone(), rx);
+                match shared_key {
+                    Ok(shared_key) => println!("Shared key from bob: {}", shared_key),
+                    Err(e) => println!("Error en el acuerdo de clave de bob: {}", e),
+                }
             }
 
         });
@@ -192,7 +211,7 @@ fn main() {
     // Elegimos el metodo de fuerza bruta ya que es el mas simple y el orden de la curva es pequeño
     println!("\nEjercicio 4:");
 
-    let curve = EllipticCurve::new(905.0, 100.0);
+    let curve = EllipticCurve::new(905, 100, 1021).expect("Error in EllipticCurve::new");
     let generator = Point::new(
         Some(FiniteFieldElement::new(1006, 1021)),
         Some(FiniteFieldElement::new(416, 1021)),
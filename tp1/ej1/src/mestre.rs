@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use crate::big_int::{BigInt, BigUint};
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::finite_field_element::FiniteFieldElement;
+use crate::point::Point;
+
+// up to this bound a curve and its twist may both have an exponent smaller than the width 4 sqrt(p) of the
+// hasse interval (mestre's theorem holds for p > 457), the legendre count is used instead.
+// y^2 = x^3 + 5 over F_457 is Z/8 x Z/56 and its twist Z/6 x Z/78
+const MESTRE_MIN_PRIME: u32 = 457;
+
+// random points tried on the curve and on its twist before giving up
+const MAX_ATTEMPTS: usize = 100;
+
+// number of points of the curve with mestre's baby-step giant-step method
+// #E is in the hasse interval [p + 1 - 2 sqrt(p), p + 1 + 2 sqrt(p)] of width w = 4 sqrt(p). For a point P
+// a multiple N of its order in the interval is found with baby-step giant-step in O(p^1/4) group operations,
+// and the order itself by removing the prime factors of N. Once the lcm e of the orders of the points
+// tried has only one multiple in the interval, which is sure when e is bigger than w, that multiple is #E.
+// when the group has a small exponent the quadratic twist E' (#E + #E' = 2p + 2) is used instead, for p > 457
+// one of them has a point of order bigger than w
+pub fn order(curve: &EllipticCurve) -> Result<BigUint, String> {
+    let p = curve.field.modulus.clone();
+    if p <= BigUint::from(3u32) {
+        return curve.order();
+    }
+    if p <= BigUint::from(MESTRE_MIN_PRIME) {
+        return curve.count_points();
+    }
+    let curve = if curve.is_short() {
+        curve.clone()
+    } else {
+        curve.to_short_weierstrass()?
+    };
+    let twist = quadratic_twist(&curve)?;
+
+    // hasse interval [p + 1 - s, p + 1 + s] with s = floor(2 sqrt(p))
+    let s = (&p * &BigUint::from(4u32)).sqrt();
+    let low = &(&p + &BigUint::one()) - &s;
+    let high = &(&p + &BigUint::one()) + &s;
+    let width = &high - &low;
+    let twist_sum = &(&p + &BigUint::one()) * &BigUint::from(2u32);
+
+    let mut rng = rand::thread_rng();
+    let mut exponents = [BigUint::one(), BigUint::one()];
+    for _ in 0..MAX_ATTEMPTS {
+        for (i, c) in [&curve, &twist].into_iter().enumerate() {
+            let point = Point::random(c, &mut rng)?;
+            let multiple = baby_step_giant_step(&point, &low, &width)?;
+            let order = point.order_dividing(&multiple)?;
+            exponents[i] = lcm(&exponents[i], &order);
+            // the first multiple of the exponent in the interval, done when the next one is past it
+            let e = &exponents[i];
+            let n = &(&(&(&low + e) - &BigUint::one()) / e) * e;
+            if &n + e > high {
+                let n = if i == 0 { n } else { &twist_sum - &n };
+                check_hasse(&p, &n)?;
+                return Ok(n);
+            }
+        }
+    }
+    Err(format!("Error in mestre no point of large enough order found over F_{}", p))
+}
+
+// y^2 = x^3 + a d^2 x + b d^3 for a quadratic non residue d, isomorphic to the curve over F_p^2 but not over F_p
+fn quadratic_twist(curve: &EllipticCurve) -> Result<EllipticCurve, String> {
+    let mut rng = rand::thread_rng();
+    let d = loop {
+        let d = curve.field.random_element(&mut rng);
+        if d.legendre()? == -1 {
+            break d;
+        }
+    };
+    let a = curve.a4.clone() * d.square();
+    let b = curve.a6.clone() * d.square() * &d;
+    EllipticCurve::new(a.value, b.value, curve.field.modulus.clone())
+}
+
+// hasse: |#E - p - 1| <= 2 sqrt(p), (#E - p - 1)^2 <= 4p
+fn check_hasse(p: &BigUint, n: &BigUint) -> Result<(), String> {
+    let t = &BigInt::from(n) - &BigInt::from(p + &BigUint::one());
+    if &t * &t > BigInt::from(p * &BigUint::from(4u32)) {
+        return Err(format!("Error in mestre {} points is outside the hasse interval of F_{}", n, p));
+    }
+    Ok(())
+}
+
+fn lcm(a: &BigUint, b: &BigUint) -> BigUint {
+    &(a / &a.gcd(b)) * b
+}
+
+fn scalar_mul(point: &Point, n: &BigUint) -> Result<Point, String> {
+    point.scalar_mul_montgomery(BigInt::from(n))
+}
+
+// some N > 0 with N P = O, searching the interval [low, low + width]
+// baby steps: jP for 1 <= j <= m with m = sqrt(width), indexed by x coordinate
+// giant steps: R_i = (low + m + i(2m + 1)) P, R_i = jP or R_i = -jP give N = low + m + i(2m + 1) -/+ j,
+// so each giant step covers 2m + 1 candidates
+fn baby_step_giant_step(point: &Point, low: &BigUint, width: &BigUint) -> Result<BigUint, String> {
+    let m = &width.sqrt() + &BigUint::one();
+    let m_u64 = m.to_u64().ok_or("Error in baby_step_giant_step interval too big")?;
+    let mut baby_steps: HashMap<BigUint, (u64, FiniteFieldElement)> = HashMap::new();
+    let mut multiple = point.clone();
+    for j in 1..=m_u64 {
+        if multiple.is_infinity() {
+            return Ok(BigUint::from(j));
+        }
+        let x = multiple.x.clone().expect("Error in baby_step_giant_step x").value;
+        let y = multiple.y.clone().expect("Error in baby_step_giant_step y");
+        if let Some((k, y_k)) = baby_steps.get(&x) {
+            // jP = kP or jP = -kP, the order divides j - k or j + k
+            return Ok(BigUint::from(if *y_k == y { j - k } else { j + k }));
+        }
+        baby_steps.insert(x, (j, y));
+        multiple = multiple.add(point)?;
+    }
+
+    let step = &(&m * &BigUint::from(2u32)) + &BigUint::one();
+    let giant_step = scalar_mul(point, &step)?;
+    let mut base = low + &m;
+    let mut giant = scalar_mul(point, &base)?;
+    let end = &(low + width) + &step;
+    while base <= end {
+        if giant.is_infinity() {
+            return Ok(base);
+        }
+        let x = &giant.x.clone().expect("Error in baby_step_giant_step x").value;
+        if let Some((j, y)) = baby_steps.get(x) {
+            let j = BigUint::from(*j);
+            return Ok(if giant.y.as_ref() == Some(y) { &base - &j } else { &base + &j });
+        }
+        giant = giant.add(&giant_step)?;
+        base = &base + &step;
+    }
+    Err("Error in baby_step_giant_step no multiple of the order in the interval".to_string())
+}
+
+// tests for the baby-step giant-step point counting
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_curve::GroupStructure;
+    use crate::prime_field::Fp;
+
+    #[test]
+    fn test_against_legendre_count() {
+        for (a, b, p) in [(-3, -3, 1021), (905, 100, 1021), (0, 6, 43), (2, 3, 97), (-3, 7, 4099), (0, 1, 10007), (1, 0, 10007)] {
+            let curve = EllipticCurve::new(a, b, p).unwrap();
+            // random points, so a few runs
+            for _ in 0..3 {
+                assert_eq!(order(&curve).unwrap(), curve.count_points().unwrap(), "{}", curve);
+            }
+        }
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 4099).unwrap();
+        assert_eq!(order(&curve).unwrap(), curve.count_points().unwrap());
+    }
+
+    #[test]
+    fn test_small_exponent_on_both_twists() {
+        // Z/8 x Z/56 with twist Z/6 x Z/78, both exponents are below the width 84 of the hasse interval
+        let curve = EllipticCurve::new(0, 5, 457).unwrap();
+        assert_eq!(curve.order_bsgs().unwrap(), BigUint::from(448u32));
+        assert_eq!(curve.group_structure().unwrap(), GroupStructure { n1: BigUint::from(8u32), n2: BigUint::from(56u32) });
+    }
+
+    #[test]
+    fn test_against_schoof() {
+        // 2^31 - 1 and a 40 bit prime, with the fast type level field for schoof
+        let curve = EllipticCurve::from_coefficients(Fp::<2147483647>::new(-3), Fp::new(7)).unwrap();
+        assert_eq!(order(&EllipticCurve::new(-3, 7, 2147483647u64).unwrap()).unwrap(), curve.order().unwrap());
+        let curve = EllipticCurve::from_coefficients(Fp::<1099511627791>::new(2), Fp::new(3)).unwrap();
+        assert_eq!(order(&EllipticCurve::new(2, 3, 1099511627791u64).unwrap()).unwrap(), curve.order().unwrap());
+    }
+
+    #[test]
+    fn test_small_order_point() {
+        // (1, 0) has order 2 on y^2 = x^3 - x, found among the baby steps
+        let curve = EllipticCurve::new(-1, 0, 1021).unwrap();
+        let point = Point::new(Some(FiniteFieldElement::new(1, 1021)), Some(FiniteFieldElement::new(0, 1021)), curve).unwrap();
+        assert_eq!(baby_step_giant_step(&point, &BigUint::from(958u32), &BigUint::from(126u32)).unwrap(), BigUint::from(2u32));
+    }
+}
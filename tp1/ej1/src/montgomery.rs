@@ -0,0 +1,313 @@
+use std::fmt::{Display, Formatter, self};
+use std::sync::Arc;
+
+use crate::big_int::{BigInt, BigUint};
+use crate::field::{Field, impl_field_operators};
+use crate::finite_field_element::{FiniteFieldElement, PrimeField};
+
+// precomputed values to work in Montgomery representation modulo an odd modulus m
+// with R = 2^(64 * limbs) an element a is stored as a * R mod m, so a product only needs
+// a REDC (division by R, which is a shift) instead of a full division by m
+#[derive(Debug, PartialEq)]
+pub struct MontgomeryContext {
+    pub modulus: BigUint,
+    limbs: usize,
+    // -m^-1 mod 2^64
+    m_prime: u64,
+    // R^2 mod m, used to convert into Montgomery form
+    r2: BigUint,
+}
+
+impl MontgomeryContext {
+    pub fn new(modulus: BigUint) -> Result<MontgomeryContext, String> {
+        if modulus.is_even() || modulus.is_one() {
+            return Err(format!("Montgomery form needs an odd modulus greater than 1, got {}", modulus));
+        }
+        let limbs = modulus.limbs().len();
+        // inverse of m mod 2^64 with newton iteration, every step doubles the correct bits
+        let m0 = modulus.limbs()[0];
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inverse)));
+        }
+        let r2 = &(BigUint::one() << (128 * limbs)) % &modulus;
+        Ok(MontgomeryContext {
+            modulus,
+            limbs,
+            m_prime: inverse.wrapping_neg(),
+            r2,
+        })
+    }
+
+    // montgomery reduction: given t < m * R returns t * R^-1 mod m
+    // algorithm (word by word):
+    // 1. for i in 0..limbs
+    // 2.   u <- t_i * m' mod 2^64
+    // 3.   t <- t + u * m * 2^(64 i)   (clears the i-th limb of t)
+    // 4. t <- t / R
+    // 5. if t >= m then t <- t - m
+    pub fn redc(&self, t: &BigUint) -> BigUint {
+        let n = self.limbs;
+        let m = self.modulus.limbs();
+        let mut a = t.limbs().to_vec();
+        a.resize(2 * n + 1, 0);
+        for i in 0..n {
+            let u = a[i].wrapping_mul(self.m_prime);
+            let mut carry: u128 = 0;
+            for j in 0..n {
+                let s = a[i + j] as u128 + u as u128 * m[j] as u128 + carry;
+                a[i + j] = s as u64;
+                carry = s >> 64;
+            }
+            let mut k = i + n;
+            while carry != 0 {
+                let s = a[k] as u128 + carry;
+                a[k] = s as u64;
+                carry = s >> 64;
+                k += 1;
+            }
+        }
+        let result = BigUint::from_limbs(a[n..].to_vec());
+        if result >= self.modulus {
+            &result - &self.modulus
+        } else {
+            result
+        }
+    }
+
+    // a * b * R^-1 mod m
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.redc(&(a * b))
+    }
+
+    // a -> a * R mod m
+    pub fn encode(&self, a: &BigUint) -> BigUint {
+        self.mul(&(a % &self.modulus), &self.r2)
+    }
+
+    // a * R -> a mod m
+    pub fn decode(&self, a: &BigUint) -> BigUint {
+        self.redc(a)
+    }
+}
+
+// field element kept in Montgomery representation, `value` holds a * R mod m
+#[derive(Debug, Clone)]
+pub struct MontgomeryFieldElement {
+    pub value: BigUint,
+    pub context: Arc<MontgomeryContext>,
+}
+
+impl MontgomeryFieldElement {
+    pub fn new<V: Into<BigInt>>(value: V, context: &Arc<MontgomeryContext>) -> MontgomeryFieldElement {
+        let value = value.into().rem_euclid(&context.modulus);
+        MontgomeryFieldElement::with_context(context.encode(&value), context)
+    }
+
+    pub fn from_element(element: &FiniteFieldElement, context: &Arc<MontgomeryContext>) -> Result<MontgomeryFieldElement, String> {
+        if element.modulus != context.modulus {
+            return Err("Modulus mismatch".to_string());
+        }
+        Ok(MontgomeryFieldElement::with_context(context.encode(&element.value), context))
+    }
+
+    pub fn to_element(&self) -> FiniteFieldElement {
+        FiniteFieldElement::new(self.context.decode(&self.value), self.context.modulus.clone())
+    }
+
+    fn check_modulus(&self, b: &MontgomeryFieldElement) -> Result<(), String> {
+        if self.context.modulus != b.context.modulus {
+            Err("Modulus mismatch".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    // values are kept padded to the number of limbs of the modulus, like FiniteFieldElement
+    fn with_context(value: BigUint, context: &Arc<MontgomeryContext>) -> MontgomeryFieldElement {
+        MontgomeryFieldElement {
+            value: value.with_width(context.limbs),
+            context: context.clone(),
+        }
+    }
+
+    fn with_value(&self, value: BigUint) -> MontgomeryFieldElement {
+        MontgomeryFieldElement::with_context(value, &self.context)
+    }
+
+    // addition and subtraction are the same as in the normal representation since (a + b) R = aR + bR
+    pub fn add(&self, b: &MontgomeryFieldElement) -> Result<MontgomeryFieldElement, String> {
+        self.check_modulus(b)?;
+        let mut value = &self.value + &b.value;
+        if value >= self.context.modulus {
+            value = &value - &self.context.modulus;
+        }
+        Ok(self.with_value(value))
+    }
+
+    pub fn sub(&self, b: &MontgomeryFieldElement) -> Result<MontgomeryFieldElement, String> {
+        self.check_modulus(b)?;
+        let value = if self.value >= b.value {
+            &self.value - &b.value
+        } else {
+            &(&self.value + &self.context.modulus) - &b.value
+        };
+        Ok(self.with_value(value))
+    }
+
+    // REDC(aR * bR) = abR
+    pub fn mul(&self, b: &MontgomeryFieldElement) -> Result<MontgomeryFieldElement, String> {
+        self.check_modulus(b)?;
+        Ok(self.with_value(self.context.mul(&self.value, &b.value)))
+    }
+
+    pub fn div(&self, b: &MontgomeryFieldElement) -> Result<MontgomeryFieldElement, String> {
+        self.check_modulus(b)?;
+        let quotient = self.to_element().div(&b.to_element())?;
+        MontgomeryFieldElement::from_element(&quotient, &self.context)
+    }
+
+    pub fn pow<E: Into<BigInt>>(&self, b: E) -> Result<MontgomeryFieldElement, String> {
+        let exponent = b.into();
+        let base = if exponent.is_negative() {
+            MontgomeryFieldElement::new(1, &self.context).div(self)?
+        } else {
+            self.clone()
+        };
+        let exponent = exponent.magnitude();
+        let mut result = MontgomeryFieldElement::new(1, &self.context);
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul(&result)?;
+            if exponent.bit(i) {
+                result = result.mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl_field_operators!(MontgomeryFieldElement);
+
+impl std::ops::Neg for &MontgomeryFieldElement {
+    type Output = MontgomeryFieldElement;
+    fn neg(self) -> MontgomeryFieldElement {
+        self.zero() - self
+    }
+}
+
+impl std::ops::Neg for MontgomeryFieldElement {
+    type Output = MontgomeryFieldElement;
+    fn neg(self) -> MontgomeryFieldElement {
+        -&self
+    }
+}
+
+impl Field for MontgomeryFieldElement {
+    type Parent = PrimeField;
+
+    fn field(&self) -> PrimeField {
+        PrimeField {
+            modulus: self.context.modulus.clone(),
+        }
+    }
+
+    fn zero(&self) -> MontgomeryFieldElement {
+        self.with_value(BigUint::zero())
+    }
+
+    fn one(&self) -> MontgomeryFieldElement {
+        MontgomeryFieldElement::new(1, &self.context)
+    }
+
+    fn integer(&self, n: i64) -> MontgomeryFieldElement {
+        MontgomeryFieldElement::new(n, &self.context)
+    }
+
+    fn inv(&self) -> Result<MontgomeryFieldElement, String> {
+        self.one().div(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    fn successor(&self) -> Option<MontgomeryFieldElement> {
+        let next = self.clone() + self.one();
+        (!next.is_zero()).then_some(next)
+    }
+
+    fn conditional_swap(&mut self, other: &mut MontgomeryFieldElement, choice: bool) {
+        BigUint::conditional_swap(&mut self.value, &mut other.value, choice);
+    }
+}
+
+impl PartialEq for MontgomeryFieldElement {
+    fn eq(&self, other: &MontgomeryFieldElement) -> bool {
+        self.value == other.value && self.context.modulus == other.context.modulus
+    }
+}
+
+impl Display for MontgomeryFieldElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.to_element(), f)
+    }
+}
+
+// tests for the montgomery representation
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p256() -> BigUint {
+        "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap()
+    }
+
+    fn p521() -> BigUint {
+        (BigUint::one() << 521) - BigUint::one()
+    }
+
+    #[test]
+    fn test_even_modulus() {
+        assert!(MontgomeryContext::new(BigUint::from(1024u32)).is_err());
+        assert!(MontgomeryContext::new(BigUint::one()).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for p in [BigUint::from(1021u32), p256(), p521()] {
+            let context = Arc::new(MontgomeryContext::new(p.clone()).unwrap());
+            let a = FiniteFieldElement::new(-12345, p.clone());
+            let m = MontgomeryFieldElement::from_element(&a, &context).unwrap();
+            assert_eq!(m.to_element(), a);
+            assert_eq!(MontgomeryFieldElement::new(-12345, &context), m);
+        }
+    }
+
+    #[test]
+    fn test_operations_match_finite_field_element() {
+        for p in [BigUint::from(43u32), BigUint::from(1021u32), p256(), p521()] {
+            let context = Arc::new(MontgomeryContext::new(p.clone()).unwrap());
+            let a = FiniteFieldElement::new(&p - &BigUint::from(5u32), p.clone());
+            let b = FiniteFieldElement::new(&p >> 1, p.clone());
+            let ma = MontgomeryFieldElement::from_element(&a, &context).unwrap();
+            let mb = MontgomeryFieldElement::from_element(&b, &context).unwrap();
+            assert_eq!(ma.add(&mb).unwrap().to_element(), a.add(&b).unwrap());
+            assert_eq!(ma.sub(&mb).unwrap().to_element(), a.sub(&b).unwrap());
+            assert_eq!(mb.sub(&ma).unwrap().to_element(), b.sub(&a).unwrap());
+            assert_eq!(ma.mul(&mb).unwrap().to_element(), a.mul(&b).unwrap());
+            assert_eq!(ma.div(&mb).unwrap().to_element(), a.div(&b).unwrap());
+            assert_eq!(ma.pow(65537).unwrap().to_element(), a.pow(65537).unwrap());
+            assert_eq!(ma.pow(-2).unwrap().to_element(), a.pow(-2).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_modulus_mismatch() {
+        let c1 = Arc::new(MontgomeryContext::new(BigUint::from(43u32)).unwrap());
+        let c2 = Arc::new(MontgomeryContext::new(BigUint::from(1021u32)).unwrap());
+        let a = MontgomeryFieldElement::new(3, &c1);
+        let b = MontgomeryFieldElement::new(3, &c2);
+        assert_eq!(a.mul(&b).unwrap_err(), "Modulus mismatch");
+        assert!(MontgomeryFieldElement::from_element(&FiniteFieldElement::new(3, 43), &c2).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::big_int::{BigInt, BigUint};
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::jacobian::JacobianPoint;
+use crate::point::Point;
+
+// up to this number of points multi_scalar_mul uses straus, pippenger above
+pub const STRAUS_MAX_POINTS: usize = 32;
+
+// window of the NAF of each scalar in straus
+const STRAUS_WINDOW: usize = 4;
+
+// sum k_1 P_1 + ... + k_n P_n of points of the same curve, with straus for a few points and pippenger's
+// bucket method for many. Fails for an empty slice (there is no curve to build the point at infinity).
+// the digits and the additions branch on the scalars, so they must be public (see Point::add)
+pub fn multi_scalar_mul<F: Field, S: Into<BigInt> + Clone>(terms: &[(S, Point<F>)]) -> Result<Point<F>, String> {
+    if terms.len() <= STRAUS_MAX_POINTS {
+        straus(terms)
+    } else {
+        pippenger(terms)
+    }
+}
+
+// shamir's trick generalized (straus): one chain of doublings shared by every term instead of one per term.
+// the scalars are written in width-w NAF and each point gets a table of its odd multiples P, 3P, ...,
+// (2^(w-1) - 1)P, then from the most significant digit Q = 2Q and Q = Q + d_i P_i for the non zero digits.
+// about max log2 k_i doublings and n (log2 k_i / (w + 1) + 2^(w-2)) additions
+pub fn straus<F: Field, S: Into<BigInt> + Clone>(terms: &[(S, Point<F>)]) -> Result<Point<F>, String> {
+    let terms = signed_terms(terms)?;
+    let curve = &terms[0].1.curve;
+    let table_size = 1 << (STRAUS_WINDOW - 2);
+    let mut digits = Vec::with_capacity(terms.len());
+    let mut tables = Vec::with_capacity(terms.len());
+    for (scalar, point) in &terms {
+        digits.push(scalar.wnaf(STRAUS_WINDOW)?);
+        let double = Sum::from_point(point)?.double()?;
+        let mut table = vec![Sum::from_point(point)?];
+        for i in 1..table_size {
+            table.push(table[i - 1].add(&double)?);
+        }
+        tables.extend(table);
+    }
+    // every odd multiple normalized with a single inversion
+    let tables = Sum::to_affine_all(&tables)?;
+
+    let length = digits.iter().map(|d| d.len()).max().unwrap_or(0);
+    let mut product = Sum::infinity(curve)?;
+    for i in (0..length).rev() {
+        product = product.double()?;
+        for (j, digits) in digits.iter().enumerate() {
+            let digit = digits.get(i).copied().unwrap_or(0);
+            if digit != 0 {
+                let multiple = &tables[j * table_size + (digit.unsigned_abs() as usize - 1) / 2];
+                product = match digit > 0 {
+                    true => product.add_point(multiple)?,
+                    false => product.add_point(&multiple.negate())?,
+                };
+            }
+        }
+    }
+    product.to_affine()
+}
+
+// pippenger's bucket method: the scalars are split in windows of c bits and, for each window from the most
+// significant one, every point goes to the bucket of its digit, B_d = sum of the P_i with digit d.
+// the window sum is sum of d B_d = B_(2^c - 1) + (B_(2^c - 1) + B_(2^c - 2)) + ... with running sums, so a
+// window costs n + 2^(c+1) additions and c doublings. With c about ln n it is better than straus for many points
+pub fn pippenger<F: Field, S: Into<BigInt> + Clone>(terms: &[(S, Point<F>)]) -> Result<Point<F>, String> {
+    let terms = signed_terms(terms)?;
+    let curve = &terms[0].1.curve;
+    let window = ((terms.len() as f64).ln().ceil() as usize).max(2);
+    let bits = terms.iter().map(|(scalar, _)| scalar.bits()).max().unwrap_or(0);
+    let infinity = Sum::infinity(curve)?;
+
+    let mut product = infinity.clone();
+    for j in (0..bits.div_ceil(window)).rev() {
+        for _ in 0..window {
+            product = product.double()?;
+        }
+        let mut buckets = vec![infinity.clone(); 1 << window];
+        for (scalar, point) in &terms {
+            let digit = (0..window).fold(0, |digit, i| digit | (scalar.bit(j * window + i) as usize) << i);
+            if digit != 0 {
+                buckets[digit] = buckets[digit].add_point(point)?;
+            }
+        }
+        let mut running = infinity.clone();
+        let mut window_sum = infinity.clone();
+        for bucket in buckets.iter().skip(1).rev() {
+            running = running.add(bucket)?;
+            window_sum = window_sum.add(&running)?;
+        }
+        product = product.add(&window_sum)?;
+    }
+    product.to_affine()
+}
+
+// (|k|, P) or (|k|, -P) for a negative k, checking that the points share the curve
+fn signed_terms<F: Field, S: Into<BigInt> + Clone>(terms: &[(S, Point<F>)]) -> Result<Vec<(BigUint, Point<F>)>, String> {
+    let curve = &terms.first().ok_or("Multi scalar multiplication needs at least one point")?.1.curve;
+    terms.iter()
+        .map(|(scalar, point)| {
+            if !point.curve.eq(curve) {
+                return Err(format!("Points are not on the same curve: {} and {}", curve, point.curve));
+            }
+            let scalar: BigInt = scalar.clone().into();
+            let point = if scalar.is_negative() { point.negate() } else { point.clone() };
+            Ok((scalar.into_magnitude(), point))
+        })
+        .collect()
+}
+
+// partial sums, in jacobian coordinates on short weierstrass curves and affine otherwise
+#[derive(Clone)]
+enum Sum<F: Field> {
+    Jacobian(JacobianPoint<F>),
+    Affine(Point<F>),
+}
+
+impl<F: Field> Sum<F> {
+    fn from_point(point: &Point<F>) -> Result<Sum<F>, String> {
+        if point.curve.is_short() {
+            Ok(Sum::Jacobian(JacobianPoint::from_affine(point)?))
+        } else {
+            Ok(Sum::Affine(point.clone()))
+        }
+    }
+
+    fn infinity(curve: &EllipticCurve<F>) -> Result<Sum<F>, String> {
+        Sum::from_point(&Point::new(None, None, curve.clone())?)
+    }
+
+    fn double(&self) -> Result<Sum<F>, String> {
+        match self {
+            Sum::Jacobian(p) => Ok(Sum::Jacobian(p.double())),
+            Sum::Affine(p) => Ok(Sum::Affine(p.add(p)?)),
+        }
+    }
+
+    fn add(&self, other: &Sum<F>) -> Result<Sum<F>, String> {
+        match (self, other) {
+            (Sum::Jacobian(p), Sum::Jacobian(q)) => Ok(Sum::Jacobian(p.add(q)?)),
+            (Sum::Affine(p), Sum::Affine(q)) => Ok(Sum::Affine(p.add(q)?)),
+            _ => Err("Error in multi_scalar_mul mixed representations".to_string()),
+        }
+    }
+
+    fn add_point(&self, point: &Point<F>) -> Result<Sum<F>, String> {
+        match self {
+            Sum::Jacobian(p) => Ok(Sum::Jacobian(p.add_mixed(point)?)),
+            Sum::Affine(p) => Ok(Sum::Affine(p.add(point)?)),
+        }
+    }
+
+    fn to_affine(&self) -> Result<Point<F>, String> {
+        match self {
+            Sum::Jacobian(p) => p.to_affine(),
+            Sum::Affine(p) => Ok(p.clone()),
+        }
+    }
+
+    fn to_affine_all(sums: &[Sum<F>]) -> Result<Vec<Point<F>>, String> {
+        let jacobian: Option<Vec<JacobianPoint<F>>> = sums.iter()
+            .map(|s| match s {
+                Sum::Jacobian(p) => Some(p.clone()),
+                Sum::Affine(_) => None,
+            })
+            .collect();
+        match jacobian {
+            Some(points) => JacobianPoint::batch_to_affine(&points),
+            None => sums.iter().map(|s| s.to_affine()).collect(),
+        }
+    }
+}
+
+// tests for the multi scalar multiplication against summing each scalar_mul
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_field_element::FiniteFieldElement;
+    use rand::Rng;
+
+    fn expected<F: Field>(terms: &[(BigInt, Point<F>)]) -> Point<F> {
+        let mut sum = Point::new(None, None, terms[0].1.curve.clone()).unwrap();
+        for (k, p) in terms {
+            sum = sum.add(&p.scalar_mul(k.clone()).unwrap()).unwrap();
+        }
+        sum
+    }
+
+    fn random_terms(curve: &EllipticCurve, n: usize) -> Vec<(BigInt, Point)> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|_| {
+                let k = BigInt::from(rng.gen_range(-100000i64..100000000000));
+                (k, Point::random(curve, &mut rng).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_against_scalar_mul() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        for n in [1, 2, 3, 5, 10, 40, 100] {
+            let terms = random_terms(&curve, n);
+            let sum = expected(&terms);
+            assert_eq!(straus(&terms).unwrap(), sum, "n = {}", n);
+            assert_eq!(pippenger(&terms).unwrap(), sum, "n = {}", n);
+            assert_eq!(multi_scalar_mul(&terms).unwrap(), sum, "n = {}", n);
+        }
+
+        // zero scalars, repeated points, P and -P, the point at infinity
+        let p = Point::new(Some(FiniteFieldElement::new(379, 1021)), Some(FiniteFieldElement::new(1011, 1021)), curve.clone()).unwrap();
+        let infinity = Point::new(None, None, curve.clone()).unwrap();
+        let terms = vec![(0, p.clone()), (5, p.clone()), (5, p.negate()), (7, infinity.clone())];
+        assert!(straus(&terms).unwrap().is_infinity());
+        assert!(pippenger(&terms).unwrap().is_infinity());
+        let terms = vec![(655, p.clone()), (-1, p.clone()), (1039, p.clone())];
+        assert_eq!(straus(&terms).unwrap(), p.scalar_mul(654).unwrap());
+        assert_eq!(pippenger(&terms).unwrap(), p.scalar_mul(654).unwrap());
+
+        // big scalars
+        let k: BigUint = "0x1f3a9b7c5d2e4f6a8b0c1d2e3f405162738495a6b7c8d9eaf0b1c2d3e4f50617".parse().unwrap();
+        let terms: Vec<(BigInt, Point)> = random_terms(&curve, 4).into_iter().map(|(_, p)| (BigInt::from(&k), p)).collect();
+        assert_eq!(straus(&terms).unwrap(), expected(&terms));
+        assert_eq!(pippenger(&terms).unwrap(), expected(&terms));
+    }
+
+    #[test]
+    fn test_long_form() {
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap();
+        let terms = random_terms(&curve, 6);
+        assert_eq!(straus(&terms).unwrap(), expected(&terms));
+        assert_eq!(pippenger(&terms).unwrap(), expected(&terms));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let terms: Vec<(i64, Point)> = vec![];
+        assert!(multi_scalar_mul(&terms).is_err());
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let other = EllipticCurve::new(-1, 0, 1021).unwrap();
+        let mut terms = random_terms(&curve, 2);
+        terms.extend(random_terms(&other, 1));
+        assert!(straus(&terms).unwrap_err().starts_with("Points are not on the same curve"));
+        assert!(pippenger(&terms).is_err());
+    }
+}
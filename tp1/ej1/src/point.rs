@@ -1,18 +1,29 @@
-use crate::{elliptic_curve::EllipticCurve, finite_field_element::FiniteFieldElement};
+use crate::{big_int::{BigInt, BigUint}, elliptic_curve::EllipticCurve, field::Field, finite_field_element::FiniteFieldElement};
+use crate::jacobian::JacobianPoint;
+use crate::montgomery::{MontgomeryContext, MontgomeryFieldElement};
 use std::fmt::{Display, Formatter, self};
+use std::sync::Arc;
+use rand::Rng;
+use crate::factorization::factorize;
+
+// window of the NAF used by scalar_mul, 4 precomputed odd multiples
+pub const WNAF_WIDTH: usize = 4;
+
+// widest window of scalar_mul_wnaf, its table has 2^(w-2) points
+pub const MAX_WNAF_WIDTH: usize = 8;
 
 #[derive(Debug, PartialEq, Clone)]
 
 
-pub struct Point {
-    pub x: Option<FiniteFieldElement>,
-    pub y: Option<FiniteFieldElement>,
-    pub curve: EllipticCurve,
+pub struct Point<F: Field = FiniteFieldElement> {
+    pub x: Option<F>,
+    pub y: Option<F>,
+    pub curve: EllipticCurve<F>,
 }
 
 
-impl Point {
-    pub fn new(x: Option<FiniteFieldElement>, y: Option<FiniteFieldElement>, curve: EllipticCurve) -> Result<Point, String> {
+impl<F: Field> Point<F> {
+    pub fn new(x: Option<F>, y: Option<F>, curve: EllipticCurve<F>) -> Result<Point<F>, String> {
         // inifinity denoted as x = None, y = None
         if x.is_none() && y.is_none() {
             return Ok(Point {
@@ -26,6 +37,13 @@ impl Point {
         }
         let x = x.expect("Error in Point::new x argument");
         let y = y.expect("Error in Point::new y argument");
+
+        // the coordinates must belong to the field of the curve
+        for coordinate in [&x, &y] {
+            if coordinate.field() != curve.field {
+                return Err(format!("Coordinate {} does not belong to the field of: {}", coordinate, curve))
+            }
+        }
         
         // check that the point exists on the curve
         if !curve.check_point(x.clone(), y.clone()) {
@@ -39,18 +57,24 @@ impl Point {
         }      
     }
 
-    pub fn eq(&self, other: &Point) -> bool {
+    #[allow(clippy::should_implement_trait)]
+    pub fn eq(&self, other: &Point<F>) -> bool {
         self.x == other.x && self.y == other.y && self.curve.eq(&other.curve)
     }
 
-    fn is_infinity(&self) -> bool {
+    pub fn is_infinity(&self) -> bool {
         self.x.is_none() && self.y.is_none()
     }
 
-    pub fn add(&self, other: &Point) -> Result<Point, String> {
+    // affine group law. It branches on the special cases (a point at infinity, P = -Q and doubling), so it
+    // and the scalar multiplications built on it (scalar_mul, scalar_mul_wnaf, the double and add ones,
+    // scalar_mul_montgomery, multi_scalar_mul and JacobianPoint) are only for public scalars and points.
+    // The APIs without exceptional branches for secret scalars are scalar_mul_ladder and FixedBaseTable::mul
+    // (complete projective additions), the ones DiffieHellman uses through ValidatedDomainParameters
+    pub fn add(&self, other: &Point<F>) -> Result<Point<F>, String> {
         // checking that the points are on the same curve
         if !self.curve.eq(&other.curve) {
-            panic!("Points are not on the same curve")
+            return Err(format!("Points are not on the same curve: {} and {}", self.curve, other.curve));
         }
         // checking if one of the points is infinity
         if  self.is_infinity() {
@@ -59,73 +83,249 @@ impl Point {
         if other.is_infinity(){
             return Ok(self.clone());
         }
-        // check if the points are inverses
-        if self.x == other.x && self.y != other.y {
-            return Point::new(None, None, self.curve.clone());
-        }
-        // checking if the points are the same
-        if self.eq(other) {
-            let x = self.x.clone().expect("Error in Point::add x argument");
-            let y = self.y.clone().expect("Error in Point::add y argument");
-            let slope_1 = 
-                x.clone().pow(2).expect("Error in Point::add x^2 argument")
-                .mul(&FiniteFieldElement::new(3, x.modulus)).expect("Error in Point::add x^2 argument multiplied by 3")
-                .add(&FiniteFieldElement::new(self.curve.a.clone() as i32, x.modulus)).expect("Error in Point::add x^2 argument when adding a");
-            let slope_2 = y.clone().mul(&FiniteFieldElement::new(2, x.modulus)).expect("Error in Point::add 2y argument");
-            let slope = slope_1.div(&slope_2).expect("Error in Point::add slope argument");
-             
-            let x3 = slope.clone().pow(2).expect("Error in Point::add slope^2 argument")
-                .sub(
-                    &x.clone().mul(&FiniteFieldElement::new(2, x.modulus)).expect("Error in Point::add 2x argument")
-                ).expect("Error in Point::add slope^2 argument when subtracting 2");
-            let y3 = slope.clone().mul(
-                &x.clone().sub(&x3.clone()).expect("Error in Point::add x - x3 argument")
-            ).expect("Error in Point::add slope * (x - x3) argument")
-                .sub(&y.clone()).expect("Error in Point::add slope * (x - x3) - y argument");
-            return Point::new(Some(x3), Some(y3), self.curve.clone());
-        }
-
-        // points are different
         let x1 = self.x.clone().expect("Error in Point::add x1 argument");
         let y1 = self.y.clone().expect("Error in Point::add y1 argument");
         let x2 = other.x.clone().expect("Error in Point::add x2 argument");
         let y2 = other.y.clone().expect("Error in Point::add y2 argument");
 
-        // slope of the line between the points 
-        // s = (y2 - y1) / (x2 - x1)
-        let s = y2.clone().sub(
-            &y1.clone()
-        ).expect("Error in Point::add y2 - y1 argument")
-            .div(
-                &x2.clone().sub(&x1.clone()).expect("Error in Point::add x2 - x1 argument")
-            ).expect("Error in Point::add (y2 - y1) / (x2 - x1) argument");
-        // x3 = s^2 - (x1 + x2)
-        let x3 = s.clone().pow(2).expect("Error in Point::add s^2 argument")
-            .sub(
-                &x1.clone().add(&x2.clone()).expect("Error in Point::add x1 + x2 argument")
-            ).expect("Error in Point::add s^2 - (x1 + x2) argument");
-        // y3 = s(x1 - x3) - y1
-        let y3 = s.clone().mul(
-            &x1.clone().sub(&x3.clone()).expect("Error in Point::add x1 - x3 argument")
-        ).expect("Error in Point::add s * (x1 - x3) argument")
-            .sub(&y1.clone()).expect("Error in Point::add s * (x1 - x3) - y1 argument");
+        // check if the points are inverses, -(x, y) = (x, -y - a1 x - a3)
+        // (on a short curve a point with y = 0 is its own inverse)
+        let curve = &self.curve;
+        if x1 == x2 && y2 == curve.negate_y(&x1, &y1) {
+            return Point::new(None, None, self.curve.clone());
+        }
+        let s = if x1 == x2 {
+            // doubling, slope of the tangent line
+            // s = (3x^2 + 2a2 x + a4 - a1 y) / (2y + a1 x + a3)
+            let numerator = x1.integer(3) * x1.square() + x1.integer(2) * &curve.a2 * &x1 + &curve.a4 - curve.a1.clone() * &y1;
+            numerator.div(&(y1.clone() + &y1 + curve.a1.clone() * &x1 + &curve.a3))?
+        } else {
+            // slope of the line between the points
+            // s = (y2 - y1) / (x2 - x1)
+            (y2 - &y1).div(&(x2.clone() - &x1))?
+        };
+        // x3 = s^2 + a1 s - a2 - (x1 + x2)
+        let x3 = s.square() + curve.a1.clone() * &s - &curve.a2 - &x1 - &x2;
+        // y3 = s(x1 - x3) - y1 - a1 x3 - a3
+        let y3 = s * (x1 - &x3) - &y1 - curve.a1.clone() * &x3 - &curve.a3;
         // returning the new point
-        return Point::new(Some(x3), Some(y3), self.curve.clone());
+        Point::new(Some(x3), Some(y3), self.curve.clone())
+    }
+
+    // -P, the inverse of the point in the group
+    pub fn negate(&self) -> Point<F> {
+        match (&self.x, &self.y) {
+            (Some(x), Some(y)) => Point {
+                x: Some(x.clone()),
+                y: Some(self.curve.negate_y(x, y)),
+                curve: self.curve.clone(),
+            },
+            _ => self.clone(),
+        }
     }
 
-    pub fn scalar_mul(&self, scalar: u32) -> Result<Point, String> {
-        // starting the point at infinity
+    // image of the point on the isomorphic short weierstrass curve, see EllipticCurve::to_short_weierstrass
+    pub fn to_short_weierstrass(&self) -> Result<Point<F>, String> {
+        let curve = self.curve.to_short_weierstrass()?;
+        match (&self.x, &self.y) {
+            (Some(x), Some(y)) => {
+                let (x, y) = self.curve.short_weierstrass_point(x, y);
+                Point::new(Some(x), Some(y), curve)
+            }
+            _ => Point::new(None, None, curve),
+        }
+    }
+
+    // kP with the width 4 NAF of k, see scalar_mul_wnaf. The digits and the additions branch on k, see add
+    pub fn scalar_mul<S: Into<BigInt>>(&self, scalar: S) -> Result<Point<F>, String> {
+        self.scalar_mul_wnaf(scalar, WNAF_WIDTH)
+    }
+
+    // kP with the width-w NAF of k (see BigUint::wnaf): the odd multiples P, 3P, ..., (2^(w-1) - 1)P are
+    // precomputed and then, from the most significant digit, Q = 2Q and Q = Q + dP for the non zero digits d.
+    // negative digits are cheap since -(x, y) = (x, -y - a1 x - a3), and there is a non zero digit every w
+    // bits on average instead of every 2 bits of double and add, for 2^(w-2) - 1 additions of precomputation.
+    // on short weierstrass curves the table and the sum are in jacobian coordinates and only the result is
+    // inverted, the table entries are added with the full jacobian addition instead of the mixed one
+    pub fn scalar_mul_wnaf<S: Into<BigInt>>(&self, scalar: S, width: usize) -> Result<Point<F>, String> {
+        if !(2..=MAX_WNAF_WIDTH).contains(&width) {
+            return Err(format!("Window width must be between 2 and {}, got {}", MAX_WNAF_WIDTH, width));
+        }
+        let (base, scalar) = self.signed_scalar(scalar);
+        let digits = scalar.wnaf(width)?;
+        let table_size = 1 << (width - 2);
+        if self.curve.is_short() {
+            // P, 3P, 5P, ... adding 2P
+            let base = JacobianPoint::from_affine(&base)?;
+            let double = base.double();
+            let mut table = vec![base];
+            for i in 1..table_size {
+                table.push(table[i - 1].add(&double)?);
+            }
+            let mut product = JacobianPoint::from_affine(&Point::new(None, None, self.curve.clone())?)?;
+            for digit in digits.iter().rev() {
+                product = product.double();
+                if *digit != 0 {
+                    let multiple = &table[(digit.unsigned_abs() as usize - 1) / 2];
+                    product = match digit > &0 {
+                        true => product.add(multiple)?,
+                        false => product.add(&multiple.negate())?,
+                    };
+                }
+            }
+            return product.to_affine();
+        }
+        let double = base.add(&base)?;
+        let mut table = vec![base];
+        for i in 1..table_size {
+            table.push(table[i - 1].add(&double)?);
+        }
         let mut product = Point::new(None, None, self.curve.clone())?;
+        for digit in digits.iter().rev() {
+            product = product.add(&product)?;
+            if *digit != 0 {
+                let multiple = &table[(digit.unsigned_abs() as usize - 1) / 2];
+                product = match digit > &0 {
+                    true => product.add(multiple)?,
+                    false => product.add(&multiple.negate())?,
+                };
+            }
+        }
+        Ok(product)
+    }
 
-        let mut i = 0;
-        while i < scalar {
-            product = product.add(self)?;
-            i += 1;
+    // double and add over the bits of k from the most significant one:
+    // Q = O, for each bit Q = 2Q and Q = Q + P when the bit is 1
+    pub fn scalar_mul_left_to_right<S: Into<BigInt>>(&self, scalar: S) -> Result<Point<F>, String> {
+        let (base, scalar) = self.signed_scalar(scalar);
+        if self.curve.is_short() {
+            // mixed additions of the affine P
+            let mut product = JacobianPoint::from_affine(&Point::new(None, None, self.curve.clone())?)?;
+            for i in (0..scalar.bits()).rev() {
+                product = product.double();
+                if scalar.bit(i) {
+                    product = product.add_mixed(&base)?;
+                }
+            }
+            return product.to_affine();
+        }
+        let mut product = Point::new(None, None, self.curve.clone())?;
+        for i in (0..scalar.bits()).rev() {
+            product = product.add(&product)?;
+            if scalar.bit(i) {
+                product = product.add(&base)?;
+            }
+        }
+        Ok(product)
+    }
+
+    // double and add over the bits of k from the least significant one, keeping the doublings 2^i P:
+    // Q = O, R = P, for each bit Q = Q + R when the bit is 1 and R = 2R
+    pub fn scalar_mul_right_to_left<S: Into<BigInt>>(&self, scalar: S) -> Result<Point<F>, String> {
+        let (base, scalar) = self.signed_scalar(scalar);
+        let bits = scalar.bits();
+        if self.curve.is_short() {
+            let mut product = JacobianPoint::from_affine(&Point::new(None, None, self.curve.clone())?)?;
+            let mut doubling = JacobianPoint::from_affine(&base)?;
+            for i in 0..bits {
+                if scalar.bit(i) {
+                    product = product.add(&doubling)?;
+                }
+                // the last doubling is not used
+                if i + 1 < bits {
+                    doubling = doubling.double();
+                }
+            }
+            return product.to_affine();
+        }
+        let mut product = Point::new(None, None, self.curve.clone())?;
+        let mut doubling = base;
+        for i in 0..bits {
+            if scalar.bit(i) {
+                product = product.add(&doubling)?;
+            }
+            if i + 1 < bits {
+                doubling = doubling.add(&doubling)?;
+            }
         }
         Ok(product)
     }
 
-    pub fn naive_factor(&self, other: Point) -> Result<Option<u128>, String> {
+    // (P, |k|) or (-P, |k|) for a negative k
+    fn signed_scalar<S: Into<BigInt>>(&self, scalar: S) -> (Point<F>, BigUint) {
+        let scalar = scalar.into();
+        let base = if scalar.is_negative() { self.negate() } else { self.clone() };
+        (base, scalar.magnitude().clone())
+    }
+
+    // kP with the montgomery ladder on x-only projective coordinates (X : Z), x = X / Z, for short weierstrass
+    // curves. It keeps R0 = O, R1 = P and for each bit of k, with R1 - R0 = P all along:
+    // bit 0: R1 = R0 + R1, R0 = 2R0
+    // bit 1: R0 = R0 + R1, R1 = 2R1
+    // the bit only chooses a conditional swap before and after the same addition and doubling. `order` is
+    // a multiple of the order of the point (its order n or the number of points of the curve): k is reduced
+    // mod order, which also takes care of negative scalars, and the loop always runs over the bits of
+    // order, so the sequence of field operations doesn't depend on k. y is recovered at the end from x(kP),
+    // x((k + 1)P) and P; the exceptional cases kP = O and kP = -P are picked with conditional swaps after
+    // computing the general case.
+    // on a long form curve the ladder runs on the isomorphic short curve and kP is mapped back, which
+    // fails in characteristic 2 and 3
+    pub fn scalar_mul_ladder<S: Into<BigInt>>(&self, scalar: S, order: &BigUint) -> Result<Point<F>, String> {
+        if order.is_zero() {
+            return Err("The order for the ladder must not be zero".to_string());
+        }
+        if !self.curve.is_short() {
+            let product = self.to_short_weierstrass()?.scalar_mul_ladder(scalar, order)?;
+            return match (&product.x, &product.y) {
+                (Some(x), Some(y)) => {
+                    let (x, y) = self.curve.long_weierstrass_point(x, y)?;
+                    Point::new(Some(x), Some(y), self.curve.clone())
+                }
+                _ => Point::new(None, None, self.curve.clone()),
+            };
+        }
+        if self.is_infinity() {
+            return Ok(self.clone());
+        }
+        let scalar = scalar.into().rem_euclid(order);
+        let x = self.x.clone().expect("Error in Point::scalar_mul_ladder x argument");
+        let y = self.y.clone().expect("Error in Point::scalar_mul_ladder y argument");
+        let (a, b) = (&self.curve.a4, &self.curve.a6);
+
+        // R0 = O = (1 : 0), R1 = P = (x : 1)
+        let (mut x0, mut z0) = (x.one(), x.zero());
+        let (mut x1, mut z1) = (x.clone(), x.one());
+        for i in (0..order.bits()).rev() {
+            let bit = scalar.bit(i);
+            x0.conditional_swap(&mut x1, bit);
+            z0.conditional_swap(&mut z1, bit);
+            (x1, z1) = ladder_add(&x0, &z0, &x1, &z1, &x, a, b);
+            (x0, z0) = ladder_double(&x0, &z0, a, b);
+            x0.conditional_swap(&mut x1, bit);
+            z0.conditional_swap(&mut z1, bit);
+        }
+
+        // R0 = kP and R1 = (k + 1)P. When kP = O or (k + 1)P = O (kP = -P, which also covers the points of
+        // order 2, y = 0) the zero denominators are replaced by one so the general case can still be computed
+        let infinity = z0.is_zero();
+        let minus_point = z1.is_zero();
+        let one = x.one();
+        let x_k = x0.div(&select(&z0, &one, infinity))?;
+        let x_next = x1.div(&select(&z1, &one, minus_point))?;
+        // okeya-sakurai y recovery: y_k = (2b + (a + x x_k)(x + x_k) - x_next (x - x_k)^2) / 2y
+        let numerator = x.integer(2) * b + (a.clone() + x.clone() * &x_k) * (x.clone() + &x_k)
+            - x_next * (x.clone() - &x_k).square();
+        let y_k = numerator.div(&select(&(y.clone() + &y), &one, infinity || minus_point))?;
+        // -P has the same x as kP
+        let y_k = select(&y_k, &self.curve.negate_y(&x, &y), minus_point);
+        Ok(Point {
+            x: (!infinity).then_some(x_k),
+            y: (!infinity).then_some(y_k),
+            curve: self.curve.clone(),
+        })
+    }
+
+    pub fn naive_factor(&self, other: Point<F>) -> Result<Option<u128>, String> {
         let mut i: u128 = 1;
         let mut generator = self.clone();
         while generator != other {
@@ -139,7 +339,125 @@ impl Point {
     }
 }
 
-impl Display for Point {
+// x-only sum of (X1 : Z1) and (X2 : Z2) on y^2 = x^3 + ax + b knowing the x of their difference:
+// X3 = 2(X1 Z2 + X2 Z1)(X1 X2 + a Z1 Z2) + 4b Z1^2 Z2^2 - x (X1 Z2 - X2 Z1)^2
+// Z3 = (X1 Z2 - X2 Z1)^2
+fn ladder_add<F: Field>(x1: &F, z1: &F, x2: &F, z2: &F, difference: &F, a: &F, b: &F) -> (F, F) {
+    let x1z2 = x1.clone() * z2;
+    let x2z1 = x2.clone() * z1;
+    let z1z2 = z1.clone() * z2;
+    let minus = (x1z2.clone() - &x2z1).square();
+    let x3 = x1.integer(2) * (x1z2 + &x2z1) * (x1.clone() * x2 + a.clone() * &z1z2)
+        + x1.integer(4) * b * z1z2.square()
+        - difference.clone() * &minus;
+    (x3, minus)
+}
+
+// b when choice is true and a otherwise, with a conditional swap instead of a branch
+fn select<F: Field>(a: &F, b: &F, choice: bool) -> F {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    a.conditional_swap(&mut b, choice);
+    a
+}
+
+// x-only doubling of (X : Z) on y^2 = x^3 + ax + b:
+// X2 = (X^2 - a Z^2)^2 - 8b X Z^3
+// Z2 = 4Z(X^3 + a X Z^2 + b Z^3)
+fn ladder_double<F: Field>(x: &F, z: &F, a: &F, b: &F) -> (F, F) {
+    let xx = x.square();
+    let zz = z.square();
+    let zzz = zz.clone() * z;
+    let x2 = (xx.clone() - a.clone() * &zz).square() - x.integer(8) * b * x * &zzz;
+    let z2 = x.integer(4) * z * (xx * x + a.clone() * x * &zz + b.clone() * &zzz);
+    (x2, z2)
+}
+
+impl Point<FiniteFieldElement> {
+    // random affine point of the curve, trying random x until there is a y.
+    // y^2 + (a1 x + a3) y = x^3 + a2 x^2 + a4 x + a6 is solved by completing the square, which needs
+    // an odd characteristic: (2y + a1 x + a3)^2 = 4x^3 + b2 x^2 + 2b4 x + b6
+    pub fn random<R: Rng>(curve: &EllipticCurve, rng: &mut R) -> Result<Point, String> {
+        if curve.field.modulus == BigUint::from(2u32) {
+            return Err("Random points need an odd characteristic".to_string());
+        }
+        loop {
+            let x = curve.field.random_element(rng);
+            if let Some((root, other_root)) = curve.completed_square(&x).sqrt() {
+                let root = if rng.gen::<bool>() { root } else { other_root };
+                let y = (root - curve.a1.clone() * &x - &curve.a3).div(&x.integer(2))?;
+                return Point::new(Some(x), Some(y), curve.clone());
+            }
+        }
+    }
+
+    // order of the point, the smallest n > 0 with nP = O, from the number of points of the curve
+    pub fn order(&self) -> Result<BigUint, String> {
+        self.order_dividing(&self.curve.order()?)
+    }
+
+    // order of the point from a multiple N of it (N P = O): for each prime q dividing N, q is removed
+    // while (N / q) P = O
+    pub fn order_dividing(&self, multiple: &BigUint) -> Result<BigUint, String> {
+        self.order_with_factors(multiple, &factorize(multiple)?)
+    }
+
+    // order_dividing with the factorization of the multiple already known
+    pub(crate) fn order_with_factors(&self, multiple: &BigUint, factors: &[(BigUint, u32)]) -> Result<BigUint, String> {
+        // montgomery form needs an odd modulus
+        let kills = |n: &BigUint| -> Result<bool, String> {
+            if self.curve.field.modulus == BigUint::from(2u32) {
+                return Ok(self.scalar_mul(n)?.is_infinity());
+            }
+            Ok(self.scalar_mul_montgomery(n)?.is_infinity())
+        };
+        if !kills(multiple)? {
+            return Err(format!("{} is not a multiple of the order of {}", multiple, self));
+        }
+        let mut order = multiple.clone();
+        for (q, exponent) in factors {
+            for _ in 0..*exponent {
+                let candidate = &order / q;
+                if !kills(&candidate)? {
+                    break;
+                }
+                order = candidate;
+            }
+        }
+        Ok(order)
+    }
+
+    // #E / order, the index of the subgroup generated by the point
+    pub fn cofactor(&self) -> Result<BigUint, String> {
+        let n = self.curve.order()?;
+        Ok(&n / &self.order_dividing(&n)?)
+    }
+
+    // scalar multiplication running the group law on montgomery form coordinates, so every product
+    // is reduced with REDC instead of a division by the modulus. The point is converted once at the
+    // start and once at the end, and the scalar is processed with double and add so big scalars are feasible
+    pub fn scalar_mul_montgomery<S: Into<BigInt>>(&self, scalar: S) -> Result<Point, String> {
+        if self.is_infinity() {
+            return Ok(self.clone());
+        }
+        let x = self.x.clone().expect("Error in Point::scalar_mul_montgomery x argument");
+        let y = self.y.clone().expect("Error in Point::scalar_mul_montgomery y argument");
+        let context = Arc::new(MontgomeryContext::new(x.modulus.clone())?);
+        let curve = self.curve.map_coefficients(|c| MontgomeryFieldElement::from_element(c, &context))?;
+        let base = Point::new(
+            Some(MontgomeryFieldElement::from_element(&x, &context)?),
+            Some(MontgomeryFieldElement::from_element(&y, &context)?),
+            curve
+        )?;
+        let product = base.scalar_mul_left_to_right(scalar)?;
+        match (product.x, product.y) {
+            (Some(x), Some(y)) => Point::new(Some(x.to_element()), Some(y.to_element()), self.curve.clone()),
+            _ => Point::new(None, None, self.curve.clone()),
+        }
+    }
+}
+
+impl<F: Field> Display for Point<F> {
+    #[allow(clippy::partialeq_to_none)]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         if self.x == None && self.y == None {
             return write!(f, "Point: Infinity")
@@ -154,10 +472,14 @@ impl Display for Point {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::extension_field::{ExtensionField, ExtensionFieldElement};
+    use crate::finite_field_element::PrimeField;
+    use crate::prime_field::{Fp, F1021, F43};
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_new_point_exists() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
         let x = FiniteFieldElement::new(379, 1021);
         let y = FiniteFieldElement::new(1011, 1021);
         let point = Point::new(Some(x), Some(y), curve);
@@ -165,8 +487,9 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_new_point_doesnt_exists() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 17).unwrap();
         let x = FiniteFieldElement::new(1, 17);
         let y = FiniteFieldElement::new(5, 17);
         let point2: Result<Point, String> = Point::new(Some(x), Some(y), curve);
@@ -175,9 +498,23 @@ mod tests {
     }
 
     #[test]
+    fn test_new_point_wrong_field() {
+        // (379, 1011) is on the curve over F_1021 but these coordinates belong to F_1019
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let x = FiniteFieldElement::new(379, 1019);
+        let y = FiniteFieldElement::new(1011, 1019);
+        let point: Result<Point, String> = Point::new(Some(x), Some(y), curve);
+        assert_eq!(
+            point.unwrap_err(),
+            "Coordinate (value: 379,modulus: 1019) does not belong to the field of: Elliptic Curve: y^2 = x^3 + 1018x + 1018 over F_1021"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_new_point_infinity() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
-        let point3 = Point::new(None, None, curve);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let point3: Result<Point, String> = Point::new(None, None, curve);
         assert_eq!(point3.is_ok(), true);
         // check that x and y is None
         assert_eq!(point3.clone().unwrap().x, None);
@@ -185,8 +522,9 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_eq() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
         let x = FiniteFieldElement::new(379, 1021);
         let y = FiniteFieldElement::new(1011, 1021);
         let point = Point::new(Some(x.clone()), Some(y.clone()), curve.clone()).unwrap();
@@ -196,7 +534,7 @@ mod tests {
 
     #[test]
     fn test_add_same_point() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
         let x = FiniteFieldElement::new(379, 1021);
         let y = FiniteFieldElement::new(1011, 1021);
         let point = Point::new(Some(x.clone()), Some(y.clone()), curve.clone()).unwrap();
@@ -208,7 +546,7 @@ mod tests {
 
     #[test]
     fn test_add_different_points() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
         let x = FiniteFieldElement::new(379, 1021);
         let y = FiniteFieldElement::new(1011, 1021);
         let point = Point::new(Some(x.clone()), Some(y.clone()), curve.clone()).unwrap();
@@ -221,8 +559,20 @@ mod tests {
     }
 
     #[test]
+    fn test_add_points_of_different_curves() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let other = EllipticCurve::new(905, 100, 1021).unwrap();
+        let point = Point::new(Some(FiniteFieldElement::new(379, 1021)), Some(FiniteFieldElement::new(1011, 1021)), curve).unwrap();
+        let point2 = Point::new(Some(FiniteFieldElement::new(1006, 1021)), Some(FiniteFieldElement::new(416, 1021)), other.clone()).unwrap();
+        assert!(point.add(&point2).unwrap_err().starts_with("Points are not on the same curve"));
+        // also with the point at infinity of the other curve
+        assert!(point.add(&Point::new(None, None, other).unwrap()).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_scalar_mul() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
         let x = FiniteFieldElement::new(379, 1021);
         let y = FiniteFieldElement::new(1011, 1021);
         let point = Point::new(Some(x.clone()), Some(y.clone()), curve.clone()).unwrap();
@@ -238,14 +588,244 @@ mod tests {
         assert_eq!(point3.y, Some(FiniteFieldElement::new(239, 1021)));
 
         // starting at inf returns inf
-        let point = Point::new(None, None, curve.clone()).unwrap();
+        let point: Point = Point::new(None, None, curve.clone()).unwrap();
         let point3 = point.scalar_mul(2).unwrap();
         assert_eq!(point3.eq(&point), true);
     }
 
     #[test]
+    fn test_scalar_mul_double_and_add() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let point = Point::new(Some(FiniteFieldElement::new(379, 1021)), Some(FiniteFieldElement::new(1011, 1021)), curve.clone()).unwrap();
+        let infinity = Point::new(None, None, curve.clone()).unwrap();
+
+        // against adding the point k times
+        let mut expected = infinity.clone();
+        for k in 0..200u32 {
+            assert_eq!(point.scalar_mul_left_to_right(k).unwrap(), expected);
+            assert_eq!(point.scalar_mul_right_to_left(k).unwrap(), expected);
+            expected = expected.add(&point).unwrap();
+        }
+        // kP of main.rs
+        let k_p = point.scalar_mul(655).unwrap();
+        assert_eq!(k_p.x, Some(FiniteFieldElement::new(388, 1021)));
+        assert_eq!(k_p.y, Some(FiniteFieldElement::new(60, 1021)));
+        assert_eq!(point.scalar_mul_right_to_left(655).unwrap(), k_p);
+        assert_eq!(point.scalar_mul(-655).unwrap(), k_p.negate());
+        assert_eq!(point.scalar_mul_right_to_left(-655).unwrap(), k_p.negate());
+
+        // big scalars, the point has prime order 1039
+        let k: BigUint = "0x1f3a9b7c5d2e4f6a8b0c1d2e3f405162738495a6b7c8d9eaf0b1c2d3e4f50617".parse().unwrap();
+        let reduced = (&k % &BigUint::from(1039u32)).to_u64().unwrap() as u32;
+        assert_eq!(point.scalar_mul(&k).unwrap(), point.scalar_mul(reduced).unwrap());
+        assert_eq!(point.scalar_mul_right_to_left(&k).unwrap(), point.scalar_mul(reduced).unwrap());
+        assert!(point.scalar_mul(&(&k * &BigUint::from(1039u32))).unwrap().is_infinity());
+    }
+
+    #[test]
+    fn test_scalar_mul_wnaf() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let point = Point::new(Some(FiniteFieldElement::new(379, 1021)), Some(FiniteFieldElement::new(1011, 1021)), curve).unwrap();
+        let k: BigUint = "0x1f3a9b7c5d2e4f6a8b0c1d2e3f405162738495a6b7c8d9eaf0b1c2d3e4f50617".parse().unwrap();
+        for width in 2..=6 {
+            for k in [0, 1, 2, 3, 7, 8, 655, 1038, 1039, 1040, -655] {
+                assert_eq!(point.scalar_mul_wnaf(k, width).unwrap(), point.scalar_mul_left_to_right(k).unwrap());
+            }
+            assert_eq!(point.scalar_mul_wnaf(&k, width).unwrap(), point.scalar_mul_left_to_right(&k).unwrap());
+        }
+        assert_eq!(point.scalar_mul_wnaf(5, 1).unwrap_err(), "Window width must be between 2 and 8, got 1");
+        assert_eq!(point.scalar_mul_wnaf(5, 8).unwrap(), point.scalar_mul_left_to_right(5).unwrap());
+        // a table of 2^30 points
+        assert_eq!(point.scalar_mul_wnaf(5, 32).unwrap_err(), "Window width must be between 2 and 8, got 32");
+
+        // long form, affine additions
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap();
+        for point in curve.points().take(10) {
+            for k in [0, 1, 5, 77, 110, -31] {
+                assert_eq!(point.scalar_mul_wnaf(k, 3).unwrap(), point.scalar_mul_left_to_right(k).unwrap());
+            }
+        }
+        // points of order 2 of y^2 = x^3 - x, whose table repeats P and O
+        let curve = EllipticCurve::new(-1, 0, 43).unwrap();
+        for point in curve.points().take(4) {
+            for k in 0..20 {
+                assert_eq!(point.scalar_mul_wnaf(k, 4).unwrap(), point.scalar_mul_left_to_right(k).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_montgomery() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let x = FiniteFieldElement::new(379, 1021);
+        let y = FiniteFieldElement::new(1011, 1021);
+        let point = Point::new(Some(x), Some(y), curve.clone()).unwrap();
+
+        for k in [0, 1, 2, 3, 655, 1039, 1040] {
+            assert_eq!(point.scalar_mul_montgomery(k).unwrap(), point.scalar_mul(k as u32).unwrap());
+        }
+        // -kP = (x, -y) of kP
+        let minus = point.scalar_mul_montgomery(-655).unwrap();
+        let plus = point.scalar_mul(655).unwrap();
+        assert_eq!(minus.x, plus.x);
+        assert_eq!(minus.y.unwrap().add(&plus.y.unwrap()).unwrap(), FiniteFieldElement::new(0, 1021));
+    }
+
+    #[test]
+    fn test_scalar_mul_montgomery_full_size() {
+        // secp256k1: y^2 = x^3 + 7 with its generator of prime order n
+        let p: BigUint = "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f".parse().unwrap();
+        let n: BigUint = "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141".parse().unwrap();
+        let gx: BigUint = "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798".parse().unwrap();
+        let gy: BigUint = "0x483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8".parse().unwrap();
+        let curve = EllipticCurve::new(0, 7, p.clone()).unwrap();
+        let g = Point::new(
+            Some(FiniteFieldElement::new(gx, p.clone())),
+            Some(FiniteFieldElement::new(gy, p.clone())),
+            curve.clone()
+        ).unwrap();
+
+        assert!(g.scalar_mul_montgomery(n.clone()).unwrap().eq(&Point::new(None, None, curve.clone()).unwrap()));
+        assert_eq!(g.scalar_mul_montgomery(&n + &BigUint::one()).unwrap(), g);
+        // 2G computed with the plain affine group law
+        assert_eq!(g.scalar_mul_montgomery(2).unwrap(), g.add(&g).unwrap());
+    }
+
+    #[test]
+    fn test_group_law_over_other_fields() {
+        // the same points as test_scalar_mul with the type level field and the montgomery representation
+        let curve = EllipticCurve::from_coefficients(F1021::new(-3), F1021::new(-3)).unwrap();
+        let point = Point::new(Some(F1021::new(379)), Some(F1021::new(1011)), curve).unwrap();
+        let point2 = point.add(&point).unwrap();
+        assert_eq!(point2.x, Some(F1021::new(57)));
+        assert_eq!(point2.y, Some(F1021::new(914)));
+        let point3 = point.scalar_mul(3).unwrap();
+        assert_eq!(point3.x, Some(F1021::new(103)));
+        assert_eq!(point3.y, Some(F1021::new(239)));
+
+        let context = Arc::new(MontgomeryContext::new(BigUint::from(1021u32)).unwrap());
+        let curve = EllipticCurve::from_coefficients(
+            MontgomeryFieldElement::new(-3, &context),
+            MontgomeryFieldElement::new(-3, &context)
+        ).unwrap();
+        let point = Point::new(
+            Some(MontgomeryFieldElement::new(379, &context)),
+            Some(MontgomeryFieldElement::new(1011, &context)),
+            curve
+        ).unwrap();
+        let point3 = point.scalar_mul(3).unwrap();
+        assert_eq!(point3.x.unwrap().to_element(), FiniteFieldElement::new(103, 1021));
+        assert_eq!(point3.y.unwrap().to_element(), FiniteFieldElement::new(239, 1021));
+    }
+
+    #[test]
+    fn test_add_point_with_zero_y() {
+        // y^2 = x^3 - x over F_43 has (0, 0), (1, 0) and (-1, 0), points of order 2
+        let curve = EllipticCurve::from_coefficients(F43::new(-1), F43::new(0)).unwrap();
+        for x in [0, 1, -1] {
+            let point = Point::new(Some(F43::new(x)), Some(F43::new(0)), curve.clone()).unwrap();
+            assert!(point.add(&point).unwrap().is_infinity());
+        }
+    }
+
+    // closure, inverses, commutativity and associativity over every pair and triple of points
+    fn check_group_law<F: Field>(curve: &EllipticCurve<F>) {
+        check_group_law_sampled(curve, 1);
+    }
+
+    // only every step-th point as p and r, for the fields with slow arithmetic
+    fn check_group_law_sampled<F: Field>(curve: &EllipticCurve<F>, step: usize) {
+        let points: Vec<Point<F>> = curve.points().collect();
+        for p in points.iter().step_by(step) {
+            assert!(p.add(&p.negate()).unwrap().is_infinity());
+            for q in &points {
+                // add fails if the result is not on the curve
+                let sum = p.add(q).unwrap();
+                assert_eq!(sum, q.add(p).unwrap());
+                for r in points.iter().step_by(step) {
+                    assert_eq!(sum.add(r).unwrap(), p.add(&q.add(r).unwrap()).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_law_long_form() {
+        // y^2 + xy = x^3 + 1 over F_2, y^2 + y = x^3 over F_2 and y^2 = x^3 + x^2 + 1 over F_3
+        check_group_law(&EllipticCurve::from_long_coefficients(
+            Fp::<2>::new(1), Fp::new(0), Fp::new(0), Fp::new(0), Fp::new(1)
+        ).unwrap());
+        check_group_law(&EllipticCurve::from_long_coefficients(
+            Fp::<2>::new(0), Fp::new(0), Fp::new(1), Fp::new(0), Fp::new(0)
+        ).unwrap());
+        check_group_law(&EllipticCurve::from_long_coefficients(
+            Fp::<3>::new(0), Fp::new(1), Fp::new(0), Fp::new(0), Fp::new(1)
+        ).unwrap());
+        // every coefficient non zero
+        check_group_law(&EllipticCurve::from_long_coefficients(
+            F43::new(1), F43::new(-1), F43::new(3), F43::new(7), F43::new(-5)
+        ).unwrap());
+    }
+
+    #[test]
+    fn test_group_law_over_extension_fields() {
+        // y^2 = x^3 + 3 has 13 points over F_7, trace t = -5, and 7^2 + 1 - (t^2 - 2 7) = 39 over F_(7^2)
+        let field = Arc::new(ExtensionField::new(&PrimeField::new(7).unwrap(), vec![1, 0, 1]).unwrap());
+        let e = |c: Vec<i64>| ExtensionFieldElement::new(c, &field);
+        let curve = EllipticCurve::from_coefficients(e(vec![0]), e(vec![3])).unwrap();
+        assert_eq!(curve.points().count(), 39);
+        check_group_law_sampled(&curve, 10);
+        // y^2 = x^3 + x + 4 has 9 points over F_11, trace 3, and 11^2 + 1 - (9 - 2 11) = 135 over F_(11^2)
+        let field = Arc::new(ExtensionField::new(&PrimeField::new(11).unwrap(), vec![1, 0, 1]).unwrap());
+        let e = |c: Vec<i64>| ExtensionFieldElement::new(c, &field);
+        let curve = EllipticCurve::from_coefficients(e(vec![1]), e(vec![4])).unwrap();
+        let points: Vec<Point<ExtensionFieldElement>> = curve.points().collect();
+        assert_eq!(points.len(), 135);
+        for point in &points {
+            assert!(point.scalar_mul(135).unwrap().is_infinity());
+        }
+
+        // binary field F_16: y^2 + xy = x^3 + 1 has 4 points over F_2, trace -1, and 16 + 1 - 1 = 16 over F_16
+        let field = Arc::new(ExtensionField::new(&PrimeField::new(2).unwrap(), vec![1, 1, 0, 0, 1]).unwrap());
+        let e = |c: Vec<i64>| ExtensionFieldElement::new(c, &field);
+        let curve = EllipticCurve::from_long_coefficients(e(vec![1]), e(vec![0]), e(vec![0]), e(vec![0]), e(vec![1])).unwrap();
+        assert_eq!(curve.points().count(), 16);
+        check_group_law_sampled(&curve, 4);
+        // y^2 + xy = x^3 + a x^2 + a^3, within the hasse bound |16 + 1 - #E| <= 8
+        let curve = EllipticCurve::from_long_coefficients(e(vec![1]), e(vec![0, 1]), e(vec![0]), e(vec![0]), e(vec![0, 0, 0, 1])).unwrap();
+        assert!(curve.points().count().abs_diff(17) <= 8);
+        check_group_law_sampled(&curve, 4);
+    }
+
+    #[test]
+    fn test_to_short_weierstrass_is_homomorphism() {
+        let curve = EllipticCurve::from_long_coefficients(
+            F43::new(1), F43::new(-1), F43::new(3), F43::new(7), F43::new(-5)
+        ).unwrap();
+        let points: Vec<Point<F43>> = curve.points().collect();
+        for p in &points {
+            for q in &points {
+                let image = p.add(q).unwrap().to_short_weierstrass().unwrap();
+                let sum = p.to_short_weierstrass().unwrap().add(&q.to_short_weierstrass().unwrap()).unwrap();
+                assert_eq!(image, sum);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_montgomery_long_form() {
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 1021).unwrap();
+        let point = curve.points().nth(1).unwrap();
+        for k in [2, 3, 100] {
+            assert_eq!(point.scalar_mul_montgomery(k).unwrap(), point.scalar_mul(k as u32).unwrap());
+        }
+        assert_eq!(point.scalar_mul_montgomery(-100).unwrap(), point.scalar_mul(100).unwrap().negate());
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_is_on_curve() {
-        let curve = EllipticCurve::new(-3.0, -3.0);
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
         let x = FiniteFieldElement::new(379, 1021);
         let y = FiniteFieldElement::new(1011, 1021);
         let point = Point::new(Some(x.clone()), Some(y.clone()), curve.clone());
@@ -259,7 +839,7 @@ mod tests {
 
     #[test]
     fn test_naive_factor() {
-        let curve = EllipticCurve::new(905.0, 100.0);
+        let curve = EllipticCurve::new(905, 100, 1021).unwrap();
         let generator = Point::new(
             Some(FiniteFieldElement::new(1006, 1021)),
             Some(FiniteFieldElement::new(416, 1021)),
@@ -274,4 +854,203 @@ mod tests {
         println!("res: {:?}", res);
     }
 
+    #[test]
+    fn test_order() {
+        // generators of the diffie hellman exercise, the curve has 39 = 3 * 13 points
+        let curve = EllipticCurve::new(0, 6, 43).unwrap();
+        let g = Point::new(Some(FiniteFieldElement::new(13, 43)), Some(FiniteFieldElement::new(15, 43)), curve.clone()).unwrap();
+        let g2 = Point::new(Some(FiniteFieldElement::new(9, 43)), Some(FiniteFieldElement::new(2, 43)), curve.clone()).unwrap();
+        assert_eq!(g.order().unwrap(), BigUint::from(13u32));
+        assert_eq!(g.cofactor().unwrap(), BigUint::from(3u32));
+        assert_eq!(g2.order().unwrap(), BigUint::from(39u32));
+        assert_eq!(g2.cofactor().unwrap(), BigUint::one());
+        assert!(g.scalar_mul(13).unwrap().is_infinity());
+        assert!(!g2.scalar_mul(13).unwrap().is_infinity());
+
+        // from a multiple of the order, the group of the curve of main.rs is cyclic of prime order 1039
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let point = Point::random(&curve, &mut rand::thread_rng()).unwrap();
+        assert_eq!(point.order_dividing(&BigUint::from(1039u32 * 6)).unwrap(), BigUint::from(1039u32));
+        assert!(point.order_dividing(&BigUint::from(1038u32)).is_err());
+        // (1, 0) has order 2 on y^2 = x^3 - x, which has 1000 points
+        let curve = EllipticCurve::new(-1, 0, 1021).unwrap();
+        let point = Point::new(Some(FiniteFieldElement::new(1, 1021)), Some(FiniteFieldElement::new(0, 1021)), curve).unwrap();
+        assert_eq!(point.order().unwrap(), BigUint::from(2u32));
+        assert_eq!(point.cofactor().unwrap(), BigUint::from(500u32));
+        // the point at infinity has order 1
+        let infinity = Point::new(None, None, EllipticCurve::new(0, 6, 43).unwrap()).unwrap();
+        assert_eq!(infinity.order().unwrap(), BigUint::one());
+    }
+
+
+    #[test]
+    fn test_scalar_mul_ladder() {
+        let curve = EllipticCurve::new(-3, -3, 1021).unwrap();
+        let point = Point::new(Some(FiniteFieldElement::new(379, 1021)), Some(FiniteFieldElement::new(1011, 1021)), curve.clone()).unwrap();
+        // every multiple of a point of prime order 1039, including O and -P
+        let order = BigUint::from(1039u32);
+        for k in 0..1045 {
+            assert_eq!(point.scalar_mul_ladder(k, &order).unwrap(), point.scalar_mul(k).unwrap(), "k = {}", k);
+        }
+        for k in [-1, -655, -1039, -5000] {
+            assert_eq!(point.scalar_mul_ladder(k, &order).unwrap(), point.scalar_mul(k).unwrap(), "k = {}", k);
+        }
+        let k: BigUint = "0x1f3a9b7c5d2e4f6a8b0c1d2e3f405162738495a6b7c8d9eaf0b1c2d3e4f50617".parse().unwrap();
+        assert_eq!(point.scalar_mul_ladder(&k, &order).unwrap(), point.scalar_mul(&k).unwrap());
+        assert_eq!(point.scalar_mul_ladder(5, &BigUint::zero()).unwrap_err(), "The order for the ladder must not be zero");
+
+        // points of order 2 of y^2 = x^3 - x and the point at infinity, with the number of points as the order
+        let curve = EllipticCurve::new(-1, 0, 43).unwrap();
+        let order = curve.order().unwrap();
+        for point in curve.points() {
+            for k in -50..50 {
+                assert_eq!(point.scalar_mul_ladder(k, &order).unwrap(), point.scalar_mul(k).unwrap());
+            }
+        }
+
+        // long form, through the short weierstrass form, except in characteristic 2 and 3
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap();
+        let order = curve.order().unwrap();
+        for point in curve.points() {
+            for k in [0, 1, 2, 5, -5, 97, 1000] {
+                assert_eq!(point.scalar_mul_ladder(k, &order).unwrap(), point.scalar_mul(k).unwrap());
+            }
+        }
+        let curve = EllipticCurve::new_long([1, 0, 0, 0, 1], 2).unwrap();
+        let point = curve.points().nth(1).unwrap();
+        assert_eq!(point.scalar_mul_ladder(5, &curve.order().unwrap()).unwrap_err(), "There is no short weierstrass form over F_2");
+    }
+
+    // field wrapper that logs every operation, to compare the sequence of operations of two computations
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Counting(F1021);
+
+    thread_local! {
+        static OPERATIONS: std::cell::RefCell<Vec<&'static str>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    fn record(operation: &'static str) {
+        OPERATIONS.with(|operations| operations.borrow_mut().push(operation));
+    }
+
+    // operations logged while running f
+    fn operations<T>(f: impl FnOnce() -> T) -> Vec<&'static str> {
+        OPERATIONS.with(|operations| operations.borrow_mut().clear());
+        f();
+        OPERATIONS.with(|operations| operations.borrow().clone())
+    }
+
+    macro_rules! counted_operator {
+        ($tr:ident, $method:ident) => {
+            impl std::ops::$tr for Counting {
+                type Output = Counting;
+                fn $method(self, rhs: Counting) -> Counting {
+                    record(stringify!($method));
+                    Counting(std::ops::$tr::$method(self.0, rhs.0))
+                }
+            }
+
+            impl std::ops::$tr<&Counting> for Counting {
+                type Output = Counting;
+                fn $method(self, rhs: &Counting) -> Counting {
+                    std::ops::$tr::$method(self, *rhs)
+                }
+            }
+        };
+    }
+
+    counted_operator!(Add, add);
+    counted_operator!(Sub, sub);
+    counted_operator!(Mul, mul);
+
+    impl std::ops::Neg for Counting {
+        type Output = Counting;
+        fn neg(self) -> Counting {
+            record("neg");
+            Counting(-self.0)
+        }
+    }
+
+    impl Display for Counting {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            Display::fmt(&self.0, f)
+        }
+    }
+
+    impl Field for Counting {
+        type Parent = PrimeField;
+
+        fn field(&self) -> PrimeField {
+            self.0.field()
+        }
+
+        fn zero(&self) -> Counting {
+            Counting(self.0.zero())
+        }
+
+        fn one(&self) -> Counting {
+            Counting(self.0.one())
+        }
+
+        fn integer(&self, n: i64) -> Counting {
+            Counting(self.0.integer(n))
+        }
+
+        fn inv(&self) -> Result<Counting, String> {
+            record("inv");
+            Ok(Counting(self.0.inv()?))
+        }
+
+        fn is_zero(&self) -> bool {
+            record("is_zero");
+            self.0.is_zero()
+        }
+
+        fn successor(&self) -> Option<Counting> {
+            self.0.successor().map(Counting)
+        }
+
+        fn conditional_swap(&mut self, other: &mut Counting, choice: bool) {
+            record("conditional_swap");
+            self.0.conditional_swap(&mut other.0, choice);
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_ladder_is_uniform() {
+        let curve = EllipticCurve::from_coefficients(Counting(F1021::new(-3)), Counting(F1021::new(-3))).unwrap();
+        let point = Point::new(Some(Counting(F1021::new(379))), Some(Counting(F1021::new(1011))), curve).unwrap();
+
+        // the same field operations for scalars with different bits, lengths, hamming weights and signs,
+        // bigger than the order, and for the exceptional results O (k = 0 mod 1039) and -P (k = -1 mod 1039)
+        let order = BigUint::from(1039u32);
+        let ladder = operations(|| point.scalar_mul_ladder(1, &order).unwrap());
+        assert!(ladder.contains(&"conditional_swap"));
+        let big: BigInt = "0x1f3a9b7c5d2e4f6a8b0c1d2e3f405162738495a6b7c8d9eaf0b1c2d3e4f50617".parse().unwrap();
+        let scalars = [2, 655, 1024, 1037, -1, -655, 1039, 1045, 5000, 0, 1038, -1039].map(BigInt::from);
+        for k in scalars.into_iter().chain([big.clone(), -big]) {
+            assert_eq!(operations(|| point.scalar_mul_ladder(k.clone(), &order).unwrap()), ladder, "k = {}", k);
+        }
+
+        // double and add leaks the bits of the scalar
+        let double_and_add = operations(|| point.scalar_mul(1023).unwrap());
+        assert_ne!(operations(|| point.scalar_mul(1024).unwrap()), double_and_add);
+        assert_ne!(operations(|| point.scalar_mul(655).unwrap()), double_and_add);
+    }
+
+    #[test]
+    fn test_scalar_mul_single_inversion() {
+        let curve = EllipticCurve::from_coefficients(Counting(F1021::new(-3)), Counting(F1021::new(-3))).unwrap();
+        let point = Point::new(Some(Counting(F1021::new(379))), Some(Counting(F1021::new(1011))), curve).unwrap();
+        let inversions = |operations: Vec<&str>| operations.iter().filter(|o| **o == "inv").count();
+
+        // jacobian coordinates, only the conversion of the result inverts
+        for k in [2, 655, 1037] {
+            assert_eq!(inversions(operations(|| point.scalar_mul(k).unwrap())), 1);
+            assert_eq!(inversions(operations(|| point.scalar_mul_right_to_left(k).unwrap())), 1);
+            assert_eq!(inversions(operations(|| point.scalar_mul_left_to_right(k).unwrap())), 1);
+        }
+        // the affine addition inverts every time
+        assert_eq!(inversions(operations(|| point.add(&point).unwrap().add(&point).unwrap())), 2);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,281 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::big_int::BigUint;
+use crate::field::Field;
+
+// polynomial with coefficients in a field, coefficients[i] is the coefficient of x^i and there are
+// no trailing zeros (the zero polynomial has no coefficients).
+// like the field elements, the polynomial keeps the zero of its field to build constants
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<F: Field> {
+    coefficients: Vec<F>,
+    zero: F,
+}
+
+impl<F: Field> Polynomial<F> {
+    // coefficients from the constant term up, it needs at least one to know the field
+    pub fn new(coefficients: Vec<F>) -> Polynomial<F> {
+        let zero = coefficients.first().expect("Error in Polynomial::new empty coefficients").zero();
+        Polynomial::with_zero(coefficients, zero)
+    }
+
+    fn with_zero(mut coefficients: Vec<F>, zero: F) -> Polynomial<F> {
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+        Polynomial {
+            coefficients,
+            zero,
+        }
+    }
+
+    pub fn constant(c: F) -> Polynomial<F> {
+        Polynomial::new(vec![c])
+    }
+
+    // the polynomial x over the field of element
+    pub fn x(element: &F) -> Polynomial<F> {
+        Polynomial::new(vec![element.zero(), element.one()])
+    }
+
+    pub fn zero(&self) -> Polynomial<F> {
+        Polynomial::with_zero(vec![], self.zero.clone())
+    }
+
+    pub fn one(&self) -> Polynomial<F> {
+        Polynomial::constant(self.zero.one())
+    }
+
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    // coefficient of x^i
+    pub fn coefficient(&self, i: usize) -> F {
+        self.coefficients.get(i).cloned().unwrap_or_else(|| self.zero.clone())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    // None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn leading_coefficient(&self) -> F {
+        self.coefficients.last().cloned().unwrap_or_else(|| self.zero.clone())
+    }
+
+    pub fn eval(&self, x: &F) -> F {
+        // horner's rule
+        self.coefficients.iter().rev().fold(self.zero.clone(), |acc, c| acc * x + c)
+    }
+
+    pub fn scale(&self, c: &F) -> Polynomial<F> {
+        Polynomial::with_zero(self.coefficients.iter().map(|a| a.clone() * c).collect(), self.zero.clone())
+    }
+
+    // divided by the leading coefficient, the zero polynomial stays zero
+    pub fn monic(&self) -> Polynomial<F> {
+        match self.leading_coefficient().inv() {
+            Ok(inverse) => self.scale(&inverse),
+            Err(_) => self.clone(),
+        }
+    }
+
+    // long division, self = q * divisor + r with deg r < deg divisor
+    pub fn div_rem(&self, divisor: &Polynomial<F>) -> Result<(Polynomial<F>, Polynomial<F>), String> {
+        let divisor_degree = divisor.degree().ok_or("Zero division")?;
+        let inverse = divisor.leading_coefficient().inv()?;
+        let mut remainder = self.coefficients.clone();
+        if remainder.len() <= divisor_degree {
+            return Ok((self.zero(), self.clone()));
+        }
+        let mut quotient = vec![self.zero.clone(); remainder.len() - divisor_degree];
+        for i in (0..quotient.len()).rev() {
+            let factor = remainder[i + divisor_degree].clone() * &inverse;
+            if factor.is_zero() {
+                continue;
+            }
+            for (j, d) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] = remainder[i + j].clone() - factor.clone() * d;
+            }
+            quotient[i] = factor;
+        }
+        remainder.truncate(divisor_degree);
+        Ok((Polynomial::with_zero(quotient, self.zero.clone()), Polynomial::with_zero(remainder, self.zero.clone())))
+    }
+
+    pub fn rem(&self, modulus: &Polynomial<F>) -> Result<Polynomial<F>, String> {
+        Ok(self.div_rem(modulus)?.1)
+    }
+
+    // monic greatest common divisor with the euclidean algorithm
+    pub fn gcd(&self, other: &Polynomial<F>) -> Polynomial<F> {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let r = a.rem(&b).expect("Error in Polynomial::gcd rem");
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    // returns (g, s) with g = gcd(self, modulus) monic and s * self = g mod modulus
+    pub fn extended_gcd(&self, modulus: &Polynomial<F>) -> Result<(Polynomial<F>, Polynomial<F>), String> {
+        let (mut old_r, mut r) = (self.rem(modulus)?, modulus.clone());
+        let (mut old_s, mut s) = (self.one(), self.zero());
+        while !r.is_zero() {
+            let (q, remainder) = old_r.div_rem(&r)?;
+            old_r = r;
+            r = remainder;
+            let next_s = &old_s - &(&q * &s);
+            old_s = s;
+            s = next_s;
+        }
+        let inverse = old_r.leading_coefficient().inv()?;
+        Ok((old_r.scale(&inverse), old_s.scale(&inverse).rem(modulus)?))
+    }
+
+    // inverse of self modulo modulus, fails when they are not coprime
+    pub fn inverse_mod(&self, modulus: &Polynomial<F>) -> Result<Polynomial<F>, String> {
+        let (g, s) = self.extended_gcd(modulus)?;
+        if g.degree() != Some(0) {
+            return Err("Polynomials are not coprimes".to_string());
+        }
+        Ok(s)
+    }
+
+    pub fn mul_mod(&self, other: &Polynomial<F>, modulus: &Polynomial<F>) -> Result<Polynomial<F>, String> {
+        (self * other).rem(modulus)
+    }
+
+    // self^e mod modulus with square and multiply
+    pub fn pow_mod(&self, exponent: &BigUint, modulus: &Polynomial<F>) -> Result<Polynomial<F>, String> {
+        let base = self.rem(modulus)?;
+        let mut result = self.one().rem(modulus)?;
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul_mod(&result, modulus)?;
+            if exponent.bit(i) {
+                result = result.mul_mod(&base, modulus)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<F: Field> Add for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn add(self, rhs: &Polynomial<F>) -> Polynomial<F> {
+        let len = self.coefficients.len().max(rhs.coefficients.len());
+        let coefficients = (0..len).map(|i| self.coefficient(i) + rhs.coefficient(i)).collect();
+        Polynomial::with_zero(coefficients, self.zero.clone())
+    }
+}
+
+impl<F: Field> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn sub(self, rhs: &Polynomial<F>) -> Polynomial<F> {
+        let len = self.coefficients.len().max(rhs.coefficients.len());
+        let coefficients = (0..len).map(|i| self.coefficient(i) - rhs.coefficient(i)).collect();
+        Polynomial::with_zero(coefficients, self.zero.clone())
+    }
+}
+
+impl<F: Field> Mul for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn mul(self, rhs: &Polynomial<F>) -> Polynomial<F> {
+        if self.is_zero() || rhs.is_zero() {
+            return self.zero();
+        }
+        let mut coefficients = vec![self.zero.clone(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                coefficients[i + j] = coefficients[i + j].clone() + a.clone() * b;
+            }
+        }
+        Polynomial::with_zero(coefficients, self.zero.clone())
+    }
+}
+
+impl<F: Field> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn neg(self) -> Polynomial<F> {
+        Polynomial::with_zero(self.coefficients.iter().map(|c| -c.clone()).collect(), self.zero.clone())
+    }
+}
+
+// tests for polynomials over a prime field
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_field_element::FiniteFieldElement;
+    use crate::prime_field::F43;
+
+    fn poly(coefficients: &[i64]) -> Polynomial<F43> {
+        Polynomial::new(coefficients.iter().map(|c| F43::new(*c)).collect())
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = poly(&[1, 2, 3]);
+        let b = poly(&[-1, 0, -3]);
+        assert_eq!(&a + &b, poly(&[0, 2]));
+        assert_eq!((&a + &b).degree(), Some(1));
+        assert_eq!(&a - &a, a.zero());
+        assert_eq!(a.zero().degree(), None);
+        // (x + 1)(x - 1) = x^2 - 1
+        assert_eq!(&poly(&[1, 1]) * &poly(&[-1, 1]), poly(&[-1, 0, 1]));
+        assert_eq!(-&a, poly(&[-1, -2, -3]));
+        assert_eq!(a.eval(&F43::new(2)), F43::new(17));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let a = poly(&[5, 0, 7, 1, 3, 9]);
+        let b = poly(&[2, 1, 4]);
+        let (q, r) = a.div_rem(&b).unwrap();
+        assert!(r.degree() < b.degree());
+        assert_eq!(&(&q * &b) + &r, a);
+        assert_eq!(a.div_rem(&a.zero()).unwrap_err(), "Zero division");
+        assert_eq!(b.div_rem(&a).unwrap(), (a.zero(), b.clone()));
+    }
+
+    #[test]
+    fn test_gcd_and_inverse() {
+        // (x - 1)(x - 2) and (x - 1)(x + 5)
+        let a = &poly(&[-1, 1]) * &poly(&[-2, 1]);
+        let b = &poly(&[-1, 1]) * &poly(&[5, 1]);
+        assert_eq!(a.gcd(&b), poly(&[-1, 1]));
+        assert!(a.inverse_mod(&b).is_err());
+
+        let modulus = poly(&[3, 0, 0, 1, 1]);
+        let c = poly(&[7, 1, 2]);
+        let inverse = c.inverse_mod(&modulus).unwrap();
+        assert_eq!(c.mul_mod(&inverse, &modulus).unwrap(), c.one());
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        // x^p = x mod x^p - x, and x^(p^2) = x mod any factor of x^p - x
+        let x = Polynomial::x(&F43::new(0));
+        let modulus = poly(&[1, 3, 0, 1]);
+        let xp = x.pow_mod(&BigUint::from(43u32), &modulus).unwrap();
+        let mut expected = x.one();
+        for _ in 0..43 {
+            expected = expected.mul_mod(&x, &modulus).unwrap();
+        }
+        assert_eq!(xp, expected);
+
+        // with a big modulus field
+        let p: BigUint = "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap();
+        let x = Polynomial::x(&FiniteFieldElement::new(0, p.clone()));
+        let root = FiniteFieldElement::new(12345, p.clone());
+        let modulus = &x - &Polynomial::constant(root.clone());
+        // x^p mod (x - r) = r^p = r
+        assert_eq!(x.pow_mod(&p, &modulus).unwrap(), Polynomial::constant(root));
+    }
+}
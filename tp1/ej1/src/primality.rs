@@ -0,0 +1,165 @@
+use rand::Rng;
+
+use crate::big_int::{BigInt, BigUint};
+use crate::finite_field_element::FiniteFieldElement;
+
+// the first twelve primes, used for trial division and as Miller-Rabin bases
+// testing with all of them is deterministic for n < 3.3 * 10^24, which covers every 64 bit integer
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// number of extra random bases tested for integers bigger than 64 bits by is_prime,
+// a composite passes each round with probability at most 1/4
+const RANDOM_ROUNDS: usize = 32;
+
+pub fn is_prime(n: &BigUint) -> bool {
+    is_probable_prime(n, RANDOM_ROUNDS)
+}
+
+// miller-rabin primality test
+// up to 64 bits the fixed bases make the answer exact, for bigger integers `rounds` random bases are
+// tested on top of them
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    if *n < BigUint::from(2u32) {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        let p = BigUint::from(p);
+        if *n == p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
+        }
+    }
+    // n - 1 = d 2^s with d odd
+    let n_minus_one = n - &BigUint::one();
+    let mut s = 0;
+    while !n_minus_one.bit(s) {
+        s += 1;
+    }
+    let d = &n_minus_one >> s;
+
+    if !SMALL_PRIMES.iter().all(|a| miller_rabin_round(n, &d, s, &BigUint::from(*a))) {
+        return false;
+    }
+    if n.bits() <= 64 {
+        return true;
+    }
+    let mut rng = rand::thread_rng();
+    let range = n - &BigUint::from(3u32);
+    (0..rounds).all(|_| {
+        // random base in [2, n - 2]
+        let limbs = (0..n.limbs().len()).map(|_| rng.gen::<u64>()).collect();
+        let a = &(&BigUint::from_limbs(limbs) % &range) + &BigUint::from(2u32);
+        miller_rabin_round(n, &d, s, &a)
+    })
+}
+
+// the same deterministic test for a u64 as a const fn, so it can check a modulus given as a const
+// generic parameter at compile time (see prime_field::Fp)
+pub const fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    let mut i = 0;
+    while i < SMALL_PRIMES.len() {
+        if n == SMALL_PRIMES[i] {
+            return true;
+        }
+        if n.is_multiple_of(SMALL_PRIMES[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    let mut s = 0;
+    while (n - 1) >> s & 1 == 0 {
+        s += 1;
+    }
+    let d = (n - 1) >> s;
+    let mut i = 0;
+    while i < SMALL_PRIMES.len() {
+        // x <- a^d mod n with square and multiply
+        let n = n as u128;
+        let (mut x, mut base, mut e) = (1u128, SMALL_PRIMES[i] as u128, d);
+        while e > 0 {
+            if e & 1 == 1 {
+                x = x * base % n;
+            }
+            base = base * base % n;
+            e >>= 1;
+        }
+        let mut passes = x == 1 || x == n - 1;
+        let mut r = 1;
+        while !passes && r < s {
+            x = x * x % n;
+            passes = x == n - 1;
+            r += 1;
+        }
+        if !passes {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// one round with base a: n passes if a^d = 1 or a^(d 2^r) = -1 for some r < s
+fn miller_rabin_round(n: &BigUint, d: &BigUint, s: usize, a: &BigUint) -> bool {
+    let minus_one = FiniteFieldElement::new(-1, n.clone());
+    let mut x = FiniteFieldElement::new(a.clone(), n.clone()).pow(BigInt::from(d)).expect("Error in miller_rabin_round pow");
+    if x.value.is_one() || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = &x * &x;
+        if x == minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+// tests for the primality test
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trial_division(n: u64) -> bool {
+        n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+    }
+
+    #[test]
+    fn test_small_numbers() {
+        for n in 0..5000u64 {
+            assert_eq!(is_prime(&BigUint::from(n)), trial_division(n), "{}", n);
+            assert_eq!(is_prime_u64(n), trial_division(n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_pseudoprimes() {
+        // carmichael numbers and strong pseudoprimes to several small bases
+        for n in [561u64, 41041, 825265, 3215031751, 2152302898747, 3474749660383, 341550071728321, 3825123056546413051] {
+            assert!(!is_prime(&BigUint::from(n)), "{}", n);
+            assert!(!is_prime_u64(n), "{}", n);
+        }
+        // largest 64 bit prime
+        assert!(is_prime(&BigUint::from(18446744073709551557u64)));
+        assert!(is_prime_u64(18446744073709551557));
+        assert!(!is_prime_u64(u64::MAX));
+    }
+
+    #[test]
+    fn test_big_numbers() {
+        let p256: BigUint = "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff".parse().unwrap();
+        let p521 = (BigUint::one() << 521) - BigUint::one();
+        assert!(is_prime(&p256));
+        assert!(is_prime(&p521));
+        assert!(!is_prime(&(&p521 + &BigUint::from(2u32))));
+        // product of two primes bigger than 64 bits
+        assert!(!is_prime(&(&p256 * &BigUint::from(18446744073709551557u64))));
+        // 2^127 - 1 is prime, 2^128 + 1 = 59649589127497217 * 5704689200685129054721
+        assert!(is_prime(&((BigUint::one() << 127) - BigUint::one())));
+        assert!(!is_prime(&((BigUint::one() << 128) + BigUint::one())));
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt::{Display, Formatter, self};
+
+use crate::big_int::{BigInt, BigUint};
+use crate::field::Field;
+use crate::finite_field_element::{FiniteFieldElement, PrimeField};
+use crate::primality::is_prime_u64;
+
+/// Element of the prime field F_P with the modulus fixed at the type level, so elements of
+/// different fields can't be mixed:
+///
+/// ```compile_fail
+/// use ej1::prime_field::Fp;
+/// let a = Fp::<1021>::new(5);
+/// let b = Fp::<43>::new(5);
+/// let c = a + b; // mismatched types
+/// ```
+///
+/// The modulus must be a prime, checked when the type is used:
+///
+/// ```compile_fail
+/// use ej1::prime_field::Fp;
+/// let a = Fp::<15>::new(2); // 15 is not prime
+/// ```
+///
+/// `FiniteFieldElement` is still the type to use when the modulus is only known at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fp<const P: u64> {
+    value: u64,
+}
+
+// fields of the curves used in main.rs
+pub type F43 = Fp<43>;
+pub type F1021 = Fp<1021>;
+
+impl<const P: u64> Fp<P> {
+    pub const MODULUS: u64 = P;
+
+    pub fn new(value: i64) -> Fp<P> {
+        const { assert!(P > 1, "the modulus must be greater than 1") };
+        const { assert!(is_prime_u64(P), "the modulus must be prime") };
+        Fp {
+            value: (value as i128).rem_euclid(P as i128) as u64,
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    pub fn div(&self, b: &Fp<P>) -> Result<Fp<P>, String> {
+        if b.is_zero() {
+            return Err("Zero division".to_string());
+        }
+        // b^(P-2) = b^-1
+        Ok(*self * b.pow(P - 2)?)
+    }
+
+    pub fn pow<E: Into<BigInt>>(&self, b: E) -> Result<Fp<P>, String> {
+        let exponent = b.into();
+        let base = if exponent.is_negative() {
+            Fp::new(1).div(self)?
+        } else {
+            *self
+        };
+        let exponent = exponent.magnitude();
+        let mut result = Fp::new(1);
+        for i in (0..exponent.bits()).rev() {
+            result = result * result;
+            if exponent.bit(i) {
+                result = result * base;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<const P: u64> std::ops::Add for Fp<P> {
+    type Output = Fp<P>;
+    fn add(self, rhs: Fp<P>) -> Fp<P> {
+        Fp {
+            value: ((self.value as u128 + rhs.value as u128) % P as u128) as u64,
+        }
+    }
+}
+
+impl<const P: u64> std::ops::Sub for Fp<P> {
+    type Output = Fp<P>;
+    fn sub(self, rhs: Fp<P>) -> Fp<P> {
+        Fp {
+            value: ((self.value as u128 + P as u128 - rhs.value as u128) % P as u128) as u64,
+        }
+    }
+}
+
+impl<const P: u64> std::ops::Mul for Fp<P> {
+    type Output = Fp<P>;
+    fn mul(self, rhs: Fp<P>) -> Fp<P> {
+        Fp {
+            value: ((self.value as u128 * rhs.value as u128) % P as u128) as u64,
+        }
+    }
+}
+
+// division by zero panics, use `div` to get the error instead
+impl<const P: u64> std::ops::Div for Fp<P> {
+    type Output = Fp<P>;
+    fn div(self, rhs: Fp<P>) -> Fp<P> {
+        Fp::div(&self, &rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<const P: u64> std::ops::Neg for Fp<P> {
+    type Output = Fp<P>;
+    fn neg(self) -> Fp<P> {
+        Fp::new(0) - self
+    }
+}
+
+impl<const P: u64> std::ops::Add<&Fp<P>> for Fp<P> {
+    type Output = Fp<P>;
+    fn add(self, rhs: &Fp<P>) -> Fp<P> {
+        self + *rhs
+    }
+}
+
+impl<const P: u64> std::ops::Sub<&Fp<P>> for Fp<P> {
+    type Output = Fp<P>;
+    fn sub(self, rhs: &Fp<P>) -> Fp<P> {
+        self - *rhs
+    }
+}
+
+impl<const P: u64> std::ops::Mul<&Fp<P>> for Fp<P> {
+    type Output = Fp<P>;
+    fn mul(self, rhs: &Fp<P>) -> Fp<P> {
+        self * *rhs
+    }
+}
+
+impl<const P: u64> Field for Fp<P> {
+    type Parent = PrimeField;
+
+    fn field(&self) -> PrimeField {
+        PrimeField {
+            modulus: BigUint::from(P),
+        }
+    }
+
+    fn zero(&self) -> Fp<P> {
+        Fp::new(0)
+    }
+
+    fn one(&self) -> Fp<P> {
+        Fp::new(1)
+    }
+
+    fn integer(&self, n: i64) -> Fp<P> {
+        Fp::new(n)
+    }
+
+    fn inv(&self) -> Result<Fp<P>, String> {
+        Fp::new(1).div(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    fn successor(&self) -> Option<Fp<P>> {
+        (self.value + 1 < P).then(|| Fp { value: self.value + 1 })
+    }
+
+    fn conditional_swap(&mut self, other: &mut Fp<P>, choice: bool) {
+        let mask = 0u64.wrapping_sub(choice as u64);
+        let t = mask & (self.value ^ other.value);
+        self.value ^= t;
+        other.value ^= t;
+    }
+}
+
+impl<const P: u64> From<Fp<P>> for FiniteFieldElement {
+    fn from(element: Fp<P>) -> FiniteFieldElement {
+        FiniteFieldElement::new(element.value, P)
+    }
+}
+
+impl<const P: u64> TryFrom<&FiniteFieldElement> for Fp<P> {
+    type Error = String;
+
+    fn try_from(element: &FiniteFieldElement) -> Result<Fp<P>, String> {
+        const { assert!(is_prime_u64(P), "the modulus must be prime") };
+        if element.modulus != BigUint::from(P) {
+            return Err("Modulus mismatch".to_string());
+        }
+        Ok(Fp {
+            value: element.value.to_u64().expect("Error in Fp::try_from value bigger than the modulus"),
+        })
+    }
+}
+
+impl<const P: u64> Display for Fp<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.value);
+        }
+        write!(f, "(value: {},modulus: {})", self.value, P)
+    }
+}
+
+// tests for the type level prime field
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations() {
+        let a = F43::new(5);
+        let b = F43::new(-40);
+        assert_eq!(b, F43::new(3));
+        assert_eq!(a + b, F43::new(8));
+        assert_eq!(b - a, F43::new(41));
+        assert_eq!(a * b, F43::new(15));
+        assert_eq!((a / b) * b, a);
+        assert_eq!(-a, F43::new(38));
+        assert_eq!(a.pow(42).unwrap(), F43::new(1));
+        assert_eq!(a.pow(-1).unwrap() * a, F43::new(1));
+        assert_eq!(a.div(&F43::new(0)).unwrap_err(), "Zero division");
+    }
+
+    #[test]
+    fn test_matches_finite_field_element() {
+        for v in 0..1021 {
+            let a = F1021::new(v);
+            let b = F1021::new(v * 13 + 7);
+            let fa = FiniteFieldElement::from(a);
+            let fb = FiniteFieldElement::from(b);
+            assert_eq!(FiniteFieldElement::from(a * b), fa.mul(&fb).unwrap());
+            assert_eq!(FiniteFieldElement::from(a - b), fa.sub(&fb).unwrap());
+            assert_eq!(F1021::try_from(&fa).unwrap(), a);
+        }
+    }
+
+    #[test]
+    fn test_large_modulus() {
+        // 2^61 - 1 is a mersenne prime, products need the 128 bit intermediate
+        let a = Fp::<2305843009213693951>::new(-1);
+        assert_eq!(a * a, Fp::new(1));
+        assert_eq!(a + a, Fp::new(-2));
+    }
+
+    #[test]
+    fn test_try_from_modulus_mismatch() {
+        let element = FiniteFieldElement::new(5, 43);
+        assert_eq!(F1021::try_from(&element).unwrap_err(), "Modulus mismatch");
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt::{Display, Formatter, self};
+
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::finite_field_element::FiniteFieldElement;
+use crate::point::Point;
+
+// point of a short weierstrass curve in homogeneous projective coordinates (X : Y : Z), the affine point
+// (X / Z, Y / Z). The curve becomes Y^2 Z = X^3 + a X Z^2 + b Z^3 and the point at infinity is (0 : 1 : 0).
+// addition uses the complete formulas of renes, costello and batina (eprint 2015/1060): the same sequence
+// of field operations adds any two points of a curve of odd order, infinity, inverses and doubling
+// included, so there is nothing to branch on. They are the additions of FixedBaseTable, see Point::add for
+// which scalar multiplications have exceptional branches
+#[derive(Debug, Clone)]
+pub struct ProjectivePoint<F: Field = FiniteFieldElement> {
+    pub x: F,
+    pub y: F,
+    pub z: F,
+    pub curve: EllipticCurve<F>,
+}
+
+impl<F: Field> ProjectivePoint<F> {
+    // (x : y : 1), or (0 : 1 : 0) for the point at infinity
+    pub fn from_affine(point: &Point<F>) -> Result<ProjectivePoint<F>, String> {
+        let curve = &point.curve;
+        if !curve.is_short() {
+            return Err("Complete formulas need a curve in short weierstrass form".to_string());
+        }
+        let (x, y, z) = match (&point.x, &point.y) {
+            (Some(x), Some(y)) => (x.clone(), y.clone(), x.one()),
+            _ => (curve.a4.zero(), curve.a4.one(), curve.a4.zero()),
+        };
+        Ok(ProjectivePoint {
+            x,
+            y,
+            z,
+            curve: curve.clone(),
+        })
+    }
+
+    // (X / Z, Y / Z) with a single inversion
+    pub fn to_affine(&self) -> Result<Point<F>, String> {
+        if self.is_infinity() {
+            return Point::new(None, None, self.curve.clone());
+        }
+        let z_inv = self.z.inv()?;
+        Point::new(Some(self.x.clone() * &z_inv), Some(self.y.clone() * &z_inv), self.curve.clone())
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub fn negate(&self) -> ProjectivePoint<F> {
+        ProjectivePoint {
+            y: -self.y.clone(),
+            ..self.clone()
+        }
+    }
+
+    pub fn double(&self) -> ProjectivePoint<F> {
+        self.add(self).expect("Error in ProjectivePoint::double same curve")
+    }
+
+    // complete addition for an arbitrary a (algorithm 1 of the paper), with b3 = 3b
+    // 12 multiplications, 3 multiplications by a, 2 by b3 and 23 additions whatever the points are.
+    // the only error is points of different curves
+    pub fn add(&self, other: &ProjectivePoint<F>) -> Result<ProjectivePoint<F>, String> {
+        if !self.curve.eq(&other.curve) {
+            return Err(format!("Points are not on the same curve: {} and {}", self.curve, other.curve));
+        }
+        let a = &self.curve.a4;
+        let b3 = self.curve.a6.integer(3) * &self.curve.a6;
+        let (x1, y1, z1) = (&self.x, &self.y, &self.z);
+        let (x2, y2, z2) = (&other.x, &other.y, &other.z);
+
+        let mut t0 = x1.clone() * x2;
+        let mut t1 = y1.clone() * y2;
+        let mut t2 = z1.clone() * z2;
+        let mut t3 = x1.clone() + y1;
+        let mut t4 = x2.clone() + y2;
+        t3 = t3 * &t4;
+        t4 = t0.clone() + &t1;
+        t3 = t3 - &t4;
+        t4 = x1.clone() + z1;
+        let mut t5 = x2.clone() + z2;
+        t4 = t4 * &t5;
+        t5 = t0.clone() + &t2;
+        t4 = t4 - &t5;
+        t5 = y1.clone() + z1;
+        let mut x3 = y2.clone() + z2;
+        t5 = t5 * &x3;
+        x3 = t1.clone() + &t2;
+        t5 = t5 - &x3;
+        let mut z3 = a.clone() * &t4;
+        x3 = b3.clone() * &t2;
+        z3 = x3 + &z3;
+        x3 = t1.clone() - &z3;
+        z3 = t1 + &z3;
+        let mut y3 = x3.clone() * &z3;
+        t1 = t0.clone() + &t0;
+        t1 = t1 + &t0;
+        t2 = a.clone() * &t2;
+        t4 = b3 * &t4;
+        t1 = t1 + &t2;
+        t2 = t0 - &t2;
+        t2 = a.clone() * &t2;
+        t4 = t4 + &t2;
+        t0 = t1.clone() * &t4;
+        y3 = y3 + &t0;
+        t0 = t5.clone() * &t4;
+        x3 = t3.clone() * &x3;
+        x3 = x3 - &t0;
+        t0 = t3 * &t1;
+        z3 = t5 * &z3;
+        z3 = z3 + &t0;
+
+        Ok(ProjectivePoint {
+            x: x3,
+            y: y3,
+            z: z3,
+            curve: self.curve.clone(),
+        })
+    }
+}
+
+// same affine point, (X1 : Y1 : Z1) = (X2 : Y2 : Z2) iff X1 Z2 = X2 Z1 and Y1 Z2 = Y2 Z1
+impl<F: Field> PartialEq for ProjectivePoint<F> {
+    fn eq(&self, other: &ProjectivePoint<F>) -> bool {
+        self.curve.eq(&other.curve)
+            && self.x.clone() * &other.z == other.x.clone() * &self.z
+            && self.y.clone() * &other.z == other.y.clone() * &self.z
+            && self.is_infinity() == other.is_infinity()
+    }
+}
+
+impl<F: Field> Display for ProjectivePoint<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Projective Point: ({} : {} : {})", self.x, self.y, self.z)
+    }
+}
+
+// tests for the complete addition formulas against the affine group law
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime_field::F43;
+
+    #[test]
+    fn test_complete_addition() {
+        // every pair of points of curves of odd order: 39 points and 47 points over F43
+        let curves = [(0, 6), (1, 3)].map(|(a, b)| EllipticCurve::from_coefficients(F43::new(a), F43::new(b)).unwrap());
+        for curve in &curves {
+            let points: Vec<Point<F43>> = curve.points().collect();
+            assert_eq!(points.len() % 2, 1);
+            assert!(ProjectivePoint::from_affine(&points[0]).unwrap().is_infinity());
+            for p in &points {
+                let projective_p = ProjectivePoint::from_affine(p).unwrap();
+                assert_eq!(projective_p.to_affine().unwrap(), *p);
+                assert_eq!(projective_p.double().to_affine().unwrap(), p.add(p).unwrap());
+                assert_eq!(projective_p.negate().to_affine().unwrap(), p.negate());
+                for q in &points {
+                    let projective_q = ProjectivePoint::from_affine(q).unwrap();
+                    let sum = projective_p.add(&projective_q).unwrap();
+                    assert_eq!(sum.to_affine().unwrap(), p.add(q).unwrap(), "{} + {}", p, q);
+                    // with Z different from 1
+                    let sum = projective_p.double().add(&projective_q.double()).unwrap();
+                    assert_eq!(sum.to_affine().unwrap(), p.add(p).unwrap().add(&q.add(q).unwrap()).unwrap());
+                }
+            }
+        }
+
+        // the same point with another Z, (X l : Y l : Z l) for l != 0
+        let point = ProjectivePoint::from_affine(&curves[0].points().nth(1).unwrap()).unwrap();
+        let l = F43::new(17);
+        let scaled = ProjectivePoint {
+            x: point.x * l,
+            y: point.y * l,
+            z: point.z * l,
+            curve: point.curve.clone(),
+        };
+        assert_eq!(scaled, point);
+        assert_eq!(scaled.to_affine().unwrap(), point.to_affine().unwrap());
+
+        // points of another curve and curves in long form are rejected
+        let other = ProjectivePoint::from_affine(&curves[1].points().nth(1).unwrap()).unwrap();
+        assert!(point.add(&other).unwrap_err().starts_with("Points are not on the same curve"));
+        let curve = EllipticCurve::new_long([1, -1, 3, 7, -5], 97).unwrap();
+        assert!(ProjectivePoint::from_affine(&curve.points().nth(1).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use crate::big_int::{BigInt, BigUint};
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::{Field, FiniteField};
+use crate::finite_field_element::FiniteFieldElement;
+use crate::polynomial::Polynomial;
+use crate::primality::is_prime;
+
+// schoof's algorithm for the number of points of y^2 = x^3 + ax + b over F_p, p > 3
+// #E = p + 1 - t where t is the trace of the frobenius endomorphism pi(x, y) = (x^p, y^p), which
+// satisfies pi^2 - t pi + p = 0 and |t| <= 2 sqrt(p) (hasse). Over an extension F_q the same holds
+// with q in place of p, so below p is the size of the field of the curve.
+// t mod l is found for small primes l until their product is bigger than 4 sqrt(p), and t is
+// recovered with the chinese remainder theorem:
+// - l = 2: t is even iff the curve has a point of order 2, iff gcd(x^p - x, x^3 + ax + b) != 1
+// - l odd: the relation is checked on the l-torsion points, working with polynomials modulo the
+//   division polynomial psi_l, whose roots are their x coordinates
+pub fn order<F: Field>(curve: &EllipticCurve<F>) -> Result<BigUint, String> {
+    let t = trace_of_frobenius(curve)?;
+    BigUint::try_from(&BigInt::from(&curve.field.size() + &BigUint::one()) - &t)
+}
+
+pub fn trace_of_frobenius<F: Field>(curve: &EllipticCurve<F>) -> Result<BigInt, String> {
+    if !curve.is_short() {
+        return Err("Schoof's algorithm needs a curve in short weierstrass form".to_string());
+    }
+    if curve.field.characteristic() <= BigUint::from(3u32) {
+        return Err(format!("Schoof's algorithm needs a characteristic bigger than 3, got {}", curve.field.characteristic()));
+    }
+    let p = &curve.field.size();
+    let mut division_polynomials = DivisionPolynomials::new(curve);
+
+    // t mod 2
+    let f = division_polynomials.curve_polynomial.clone();
+    let x = Polynomial::x(&curve.a4);
+    let frobenius_x = x.pow_mod(p, &f)?;
+    let mut t = if (&frobenius_x - &x).gcd(&f).degree() == Some(0) {
+        BigUint::one()
+    } else {
+        BigUint::zero()
+    };
+    let mut product = BigUint::from(2u32);
+
+    // until product > 4 sqrt(p), product^2 > 16p
+    let bound = p * &BigUint::from(16u32);
+    let mut l = 3u64;
+    while &product * &product <= bound {
+        if is_prime(&BigUint::from(l)) && (p % &BigUint::from(l)).to_u64() != Some(0) {
+            let t_l = trace_mod(curve, &mut division_polynomials, l)?;
+            // t = t_l mod l and t mod product: t + product k with k = (t_l - t) / product mod l
+            let k = (FiniteFieldElement::new(t_l, l) - FiniteFieldElement::new(t.clone(), l))
+                .div(&FiniteFieldElement::new(product.clone(), l))?;
+            t = &t + &(&product * &k.value);
+            product = &product * &BigUint::from(l);
+        }
+        l += 2;
+    }
+    // t is the representative in (-product / 2, product / 2]
+    if t > (&product >> 1) {
+        Ok(&BigInt::from(t) - &BigInt::from(product))
+    } else {
+        Ok(BigInt::from(t))
+    }
+}
+
+// t mod l for an odd prime l != p
+// the computation runs modulo the division polynomial, when an inversion fails the gcd is a factor of
+// psi_l whose roots are a subset of the l-torsion closed under the frobenius (its coefficients are in F_p),
+// on which the relation holds too, so the computation restarts modulo that factor
+fn trace_mod<F: Field>(curve: &EllipticCurve<F>, division_polynomials: &mut DivisionPolynomials<F>, l: u64) -> Result<u64, String> {
+    let mut h = division_polynomials.get(l as usize).monic();
+    loop {
+        match TorsionRing::new(curve, h.clone())?.trace_mod(l)? {
+            Ok(t) => return Ok(t),
+            Err(factor) => h = factor,
+        }
+    }
+}
+
+// endomorphism (x, y) -> (a(x), b(x) y) restricted to the roots of h, None is the zero endomorphism
+type Endomorphism<F> = Option<(Polynomial<F>, Polynomial<F>)>;
+
+// arithmetic of the endomorphisms modulo h. Additions return Err(factor) when they need to invert a
+// polynomial that shares a factor with h
+struct TorsionRing<F: Field> {
+    h: Polynomial<F>,
+    // x^3 + ax + b mod h
+    f: Polynomial<F>,
+    a: F,
+    p: BigUint,
+}
+
+impl<F: Field> TorsionRing<F> {
+    fn new(curve: &EllipticCurve<F>, h: Polynomial<F>) -> Result<TorsionRing<F>, String> {
+        let f = curve_polynomial(curve).rem(&h)?;
+        Ok(TorsionRing {
+            h,
+            f,
+            a: curve.a4.clone(),
+            p: curve.field.size(),
+        })
+    }
+
+    fn mul(&self, a: &Polynomial<F>, b: &Polynomial<F>) -> Result<Polynomial<F>, String> {
+        a.mul_mod(b, &self.h)
+    }
+
+    fn inverse(&self, a: &Polynomial<F>) -> Result<Result<Polynomial<F>, Polynomial<F>>, String> {
+        let (g, s) = a.extended_gcd(&self.h)?;
+        if g.degree() == Some(0) {
+            Ok(Ok(s))
+        } else {
+            Ok(Err(g))
+        }
+    }
+
+    // group law on the images, with the slope m y so that y^2 is replaced by f:
+    // - chord: m = (b2 - b1) / (a2 - a1)
+    // - tangent: m = (3a1^2 + a) / (2 b1 f)
+    // x3 = m^2 f - a1 - a2, b3 = m (a1 - x3) - b1
+    fn add(&self, p: &Endomorphism<F>, q: &Endomorphism<F>) -> Result<Result<Endomorphism<F>, Polynomial<F>>, String> {
+        let ((a1, b1), (a2, b2)) = match (p, q) {
+            (None, _) => return Ok(Ok(q.clone())),
+            (_, None) => return Ok(Ok(p.clone())),
+            (Some(p), Some(q)) => (p, q),
+        };
+        let m = if a1 == a2 {
+            if (b1 + b2).is_zero() {
+                return Ok(Ok(None));
+            }
+            if b1 != b2 {
+                // equal on some roots of h and opposite on the others
+                return Ok(Err((b1 - b2).gcd(&self.h)));
+            }
+            let numerator = &self.mul(&a1.scale(&self.a.integer(3)), a1)? + &Polynomial::constant(self.a.clone());
+            let denominator = self.mul(&b1.scale(&self.a.integer(2)), &self.f)?;
+            match self.inverse(&denominator)? {
+                Ok(inverse) => self.mul(&numerator, &inverse)?,
+                Err(factor) => return Ok(Err(factor)),
+            }
+        } else {
+            match self.inverse(&(a2 - a1))? {
+                Ok(inverse) => self.mul(&(b2 - b1), &inverse)?,
+                Err(factor) => return Ok(Err(factor)),
+            }
+        };
+        let x3 = &(&self.mul(&self.mul(&m, &m)?, &self.f)? - a1) - a2;
+        let y3 = &self.mul(&m, &(a1 - &x3))? - b1;
+        Ok(Ok(Some((x3, y3))))
+    }
+
+    // n p with double and add
+    fn scalar_mul(&self, n: u64, p: &Endomorphism<F>) -> Result<Result<Endomorphism<F>, Polynomial<F>>, String> {
+        let mut result = None;
+        for i in (0..u64::BITS - n.leading_zeros()).rev() {
+            result = match self.add(&result, &result)? {
+                Ok(r) => r,
+                Err(factor) => return Ok(Err(factor)),
+            };
+            if (n >> i) & 1 == 1 {
+                result = match self.add(&result, p)? {
+                    Ok(r) => r,
+                    Err(factor) => return Ok(Err(factor)),
+                };
+            }
+        }
+        Ok(Ok(result))
+    }
+
+    // finds c in [0, l) with pi^2 + (p mod l) = c pi
+    fn trace_mod(&self, l: u64) -> Result<Result<u64, Polynomial<F>>, String> {
+        let x = Polynomial::x(&self.a).rem(&self.h)?;
+        // pi = (x^p, f^((p - 1) / 2) y), pi^2 = (x^(p^2), f^((p^2 - 1) / 2) y)
+        let frobenius_x = x.pow_mod(&self.p, &self.h)?;
+        let frobenius_y = self.f.pow_mod(&(&self.p >> 1), &self.h)?;
+        let frobenius = Some((frobenius_x.clone(), frobenius_y.clone()));
+        let frobenius_squared = Some((
+            frobenius_x.pow_mod(&self.p, &self.h)?,
+            self.mul(&frobenius_y, &frobenius_y.pow_mod(&self.p, &self.h)?)?,
+        ));
+        let identity = Some((x.clone(), x.one()));
+
+        let p_mod_l = (&self.p % &BigUint::from(l)).to_u64().expect("Error in TorsionRing::trace_mod p mod l");
+        let q = match self.scalar_mul(p_mod_l, &identity)? {
+            Ok(q) => q,
+            Err(factor) => return Ok(Err(factor)),
+        };
+        let sum = match self.add(&frobenius_squared, &q)? {
+            Ok(sum) => sum,
+            Err(factor) => return Ok(Err(factor)),
+        };
+        if sum.is_none() {
+            return Ok(Ok(0));
+        }
+        let mut multiple = frobenius.clone();
+        for c in 1..l {
+            if multiple == sum {
+                return Ok(Ok(c));
+            }
+            multiple = match self.add(&multiple, &frobenius)? {
+                Ok(multiple) => multiple,
+                Err(factor) => return Ok(Err(factor)),
+            };
+        }
+        Err(format!("Error in schoof no trace found modulo {}", l))
+    }
+}
+
+// x^3 + ax + b
+fn curve_polynomial<F: Field>(curve: &EllipticCurve<F>) -> Polynomial<F> {
+    let a = &curve.a4;
+    Polynomial::new(vec![curve.a6.clone(), a.clone(), a.zero(), a.one()])
+}
+
+// division polynomials of y^2 = x^3 + ax + b without the factor y:
+// psi_n = f_n(x) for odd n and psi_n = y f_n(x) for even n, using y^2 = x^3 + ax + b
+pub struct DivisionPolynomials<F: Field> {
+    curve_polynomial: Polynomial<F>,
+    polynomials: Vec<Polynomial<F>>,
+}
+
+impl<F: Field> DivisionPolynomials<F> {
+    // f_0 = 0, f_1 = 1, f_2 = 2
+    // f_3 = 3x^4 + 6ax^2 + 12bx - a^2
+    // f_4 = 4(x^6 + 5ax^4 + 20bx^3 - 5a^2x^2 - 4abx - 8b^2 - a^3)
+    pub fn new(curve: &EllipticCurve<F>) -> DivisionPolynomials<F> {
+        let (a, b) = (&curve.a4, &curve.a6);
+        let zero = a.zero();
+        let n = |n: i64| a.integer(n);
+        let f3 = Polynomial::new(vec![-a.square(), n(12) * b, n(6) * a, zero.clone(), n(3)]);
+        let f4 = Polynomial::new(vec![
+            -(n(8) * b.square()) - a.square() * a,
+            -(n(4) * a * b),
+            -(n(5) * a.square()),
+            n(20) * b,
+            n(5) * a,
+            zero.clone(),
+            n(1),
+        ]).scale(&n(4));
+        DivisionPolynomials {
+            curve_polynomial: curve_polynomial(curve),
+            polynomials: vec![Polynomial::new(vec![zero.clone()]), Polynomial::constant(n(1)), Polynomial::constant(n(2)), f3, f4],
+        }
+    }
+
+    // f_n, extending the list with the recurrences
+    // f_2m+1 = F^2 f_m+2 f_m^3 - f_m-1 f_m+1^3 for m even, f_m+2 f_m^3 - F^2 f_m-1 f_m+1^3 for m odd
+    // f_2m = f_m (f_m+2 f_m-1^2 - f_m-2 f_m+1^2) / 2
+    // where F = x^3 + ax + b replaces the y^4 of the products of even terms
+    pub fn get(&mut self, n: usize) -> Polynomial<F> {
+        while self.polynomials.len() <= n {
+            let k = self.polynomials.len();
+            let m = k / 2;
+            let f = &self.polynomials;
+            let next = if k % 2 == 1 {
+                let cube = |p: &Polynomial<F>| &(p * p) * p;
+                let (mut first, mut second) = (&f[m + 2] * &cube(&f[m]), &f[m - 1] * &cube(&f[m + 1]));
+                let curve_squared = &self.curve_polynomial * &self.curve_polynomial;
+                if m.is_multiple_of(2) {
+                    first = &first * &curve_squared;
+                } else {
+                    second = &second * &curve_squared;
+                }
+                &first - &second
+            } else {
+                let half = self.curve_polynomial.leading_coefficient().integer(2).inv()
+                    .expect("Error in DivisionPolynomials::get characteristic 2");
+                let difference = &(&f[m + 2] * &(&f[m - 1] * &f[m - 1])) - &(&f[m - 2] * &(&f[m + 1] * &f[m + 1]));
+                (&f[m] * &difference).scale(&half)
+            };
+            self.polynomials.push(next);
+        }
+        self.polynomials[n].clone()
+    }
+}
+
+// tests for schoof's algorithm
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+    use crate::prime_field::Fp;
+
+    #[test]
+    fn test_division_polynomials() {
+        // the roots of psi_n are the x coordinates of the points of order dividing n
+        type F101 = Fp<101>;
+        let curve = EllipticCurve::from_coefficients(F101::new(-3), F101::new(-3)).unwrap();
+        let mut division_polynomials = DivisionPolynomials::new(&curve);
+        let points: Vec<Point<F101>> = (0..101)
+            .flat_map(|x| (0..101).map(move |y| (x, y)))
+            .filter_map(|(x, y)| Point::new(Some(F101::new(x)), Some(F101::new(y)), curve.clone()).ok())
+            .collect();
+        for n in 3..12 {
+            let f = division_polynomials.get(n);
+            if n % 2 == 1 {
+                // psi_n has degree (n^2 - 1) / 2 and leading coefficient n
+                assert_eq!(f.degree(), Some((n * n - 1) / 2));
+                assert_eq!(f.leading_coefficient(), F101::new(n as i64));
+            }
+            for point in &points {
+                let is_torsion = point.scalar_mul(n as u32).unwrap().x.is_none();
+                // psi_n = y f_n for even n
+                let mut psi = f.eval(point.x.as_ref().unwrap());
+                if n % 2 == 0 {
+                    psi = psi * point.y.as_ref().unwrap();
+                }
+                assert_eq!(psi.is_zero(), is_torsion, "n = {}, {}", n, point);
+            }
+        }
+    }
+
+    #[test]
+    fn test_order_against_legendre_count() {
+        for (a, b, p) in [(-3, -3, 1021), (905, 100, 1021), (0, 6, 43), (2, 3, 97), (1, 1, 5), (2, 1, 7), (3, 8, 13), (7, 11, 1009)] {
+            let curve = EllipticCurve::new(a, b, p).unwrap();
+            assert_eq!(order(&curve).unwrap(), curve.count_points().unwrap(), "{}", curve);
+        }
+    }
+
+    #[test]
+    fn test_order_big_prime() {
+        // 2^31 - 1, far from what the O(p) count can do in a test
+        type F = Fp<2147483647>;
+        let curve = EllipticCurve::from_coefficients(F::new(-3), F::new(7)).unwrap();
+        let t = trace_of_frobenius(&curve).unwrap();
+        // hasse bound t^2 <= 4p
+        assert!(&t * &t <= BigInt::from(4u64 * 2147483647));
+        let n = order(&curve).unwrap();
+
+        // n P = O for points of the curve
+        let curve = EllipticCurve::new(-3, 7, 2147483647u64).unwrap();
+        let mut found = 0;
+        for x in 1..100 {
+            let x = FiniteFieldElement::new(x, 2147483647u64);
+            if let Some((y, _)) = (x.square() * &x - x.integer(3) * &x + x.integer(7)).sqrt() {
+                let point = Point::new(Some(x), Some(y), curve.clone()).unwrap();
+                assert!(point.scalar_mul_montgomery(n.clone()).unwrap().x.is_none());
+                assert!(point.scalar_mul_montgomery(&n + &BigUint::one()).unwrap().eq(&point));
+                found += 1;
+            }
+        }
+        assert!(found > 10);
+    }
+
+    #[test]
+    fn test_not_short_or_small_characteristic() {
+        let curve = EllipticCurve::new_long([1, 0, 0, 0, 1], 2).unwrap();
+        assert!(order(&curve).is_err());
+        let curve = EllipticCurve::new_long([1, 2, 3, 4, 5], 1021).unwrap();
+        assert_eq!(order(&curve).unwrap_err(), "Schoof's algorithm needs a curve in short weierstrass form");
+    }
+}